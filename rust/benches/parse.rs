@@ -0,0 +1,29 @@
+//! Compares the owned and borrowing V2 parsers.  Run with `cargo bench`.
+
+use smitten::Identifier;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200_000;
+
+fn time<F: FnMut()>(name: &str, mut f: F) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed: Duration = start.elapsed();
+    println!("{:<40} {:>8.1} ns/iter", name, elapsed.as_nanos() as f64 / f64::from(ITERATIONS));
+}
+
+fn main() {
+    let ids = ["chr1", "hg38:chr1:100-200_+", "hg38:chr1:100-200_+:10-50_-:1-5_+"];
+
+    for id in ids {
+        time(&format!("from_v2({})", id), || {
+            black_box(Identifier::from_v2(black_box(id)).unwrap());
+        });
+        time(&format!("parse_id_ref({})", id), || {
+            black_box(Identifier::parse_id_ref(black_box(id)).unwrap());
+        });
+    }
+}
@@ -0,0 +1,87 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use smitten::{bounding, merge_ranges, Identifier, Strand};
+
+// Calls the accessors and emitters on a successfully parsed identifier, so
+// that panics on unusual but accepted coordinates (zero starts, full-width
+// ranges) are found as well as panics in the parsers.
+fn exercise(id: &Identifier) {
+    let _ = id.length();
+    let _ = id.range_lengths();
+    let _ = id.to_bed_coords();
+    let _ = id.to_bed();
+    let _ = id.as_interval();
+    let _ = id.is_degenerate();
+    let _ = id.to_v0_string();
+    let _ = id.to_v1_string();
+    let _ = id.to_all_versions();
+    let _ = id.absolute_range();
+    let _ = id.normalized_strand();
+    let _ = id.normalized_or_self();
+    let _ = id.canonical();
+    let _ = id.to_gff_fields();
+    let _ = id.validate_chain();
+    let _ = id.explain();
+    let _ = id.fold_innermost();
+    let _ = id.reverse_complement();
+    let _ = id.ancestors();
+    let _ = id.truncate_depth(1);
+    let _ = id.shift(1);
+    let _ = id.shift(-1);
+    let _ = id.pad(1, 1);
+    let _ = id.clamp_to_length(1000);
+    let _ = id.mirror(1000);
+    let _ = id.project_child(1, 1, Strand::Reverse);
+    let _ = id.windows(usize::MAX, usize::MAX);
+    let _ = id.overlaps(id);
+    let _ = id.distance(id);
+    let _ = id.is_adjacent(id);
+    let _ = id.intersect(id);
+    let _ = id.contains(id);
+    let _ = bounding(core::slice::from_ref(id));
+    let _ = merge_ranges(&[id.clone(), id.clone()]);
+    for level in 0..id.depth() {
+        let _ = id.relative_to_level(level);
+    }
+    if let Ok(mut positions) = id.positions() {
+        let _ = positions.next();
+        let _ = positions.next();
+    }
+    let mut updated = id.clone();
+    if let Some(range) = id.ranges.first() {
+        let _ = updated.set_range(0, range.start, range.end, range.orientation);
+    }
+    let _ = Identifier::from_v2(&id.to_string());
+}
+
+// Feeds arbitrary UTF-8 to the parsers, under every combination of the
+// `from_unknown_format` flags.  Any result is fine, only a panic is a failure.
+fuzz_target!(|data: &[u8]| {
+    let Ok(id) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    for (zbho, allow_mixed) in [(false, false), (false, true), (true, false), (true, true)] {
+        if let Ok((parsed_id, _)) = Identifier::from_unknown_format(id, zbho, allow_mixed) {
+            exercise(&parsed_id);
+        }
+    }
+
+    let parsers: [fn(&str) -> Result<Identifier, smitten::SmittenError>; 4] = [
+        Identifier::from_v2,
+        Identifier::from_v2_lenient,
+        Identifier::from_v2_autoorient,
+        Identifier::from_v2_with_whole_strand,
+    ];
+    for parse in parsers {
+        if let Ok(parsed_id) = parse(id) {
+            exercise(&parsed_id);
+        }
+    }
+
+    if let Ok(id_ref) = Identifier::parse_id_ref(id) {
+        let _ = id_ref.ranges().count();
+        exercise(&id_ref.to_owned());
+    }
+});
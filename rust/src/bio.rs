@@ -0,0 +1,111 @@
+//! Conversions to and from the `bio_types` genome interval and strand types,
+//! enabled with the `bio-types` feature.
+
+use crate::{Identifier, Range, SmittenError, Strand};
+use bio_types::genome::{AbstractInterval, Interval};
+use bio_types::strand::Strand as BioStrand;
+
+impl From<Strand> for BioStrand {
+    fn from(strand: Strand) -> Self {
+        match strand {
+            Strand::Forward => BioStrand::Forward,
+            Strand::Reverse => BioStrand::Reverse,
+        }
+    }
+}
+
+impl TryFrom<BioStrand> for Strand {
+    type Error = SmittenError;
+
+    /// `bio_types` allows an `Unknown` strand, which has no Smitten equivalent.
+    fn try_from(strand: BioStrand) -> Result<Self, Self::Error> {
+        match strand {
+            BioStrand::Forward => Ok(Strand::Forward),
+            BioStrand::Reverse => Ok(Strand::Reverse),
+            BioStrand::Unknown => Err(SmittenError::InvalidOrientation('.')),
+        }
+    }
+}
+
+impl TryFrom<&Identifier> for (String, Interval) {
+    type Error = SmittenError;
+
+    /// Normalizes the identifier and returns its assembly identifier (empty if
+    /// there is none) with the zero-based, half-open interval of the span on
+    /// the sequence.  The strand is not part of a `bio_types` interval, use
+    /// `BioStrand::from` on the normalized orientation to keep it.
+    ///
+    /// Whole-sequence identifiers have no span and are rejected, as are
+    /// ranges starting at 0.
+    fn try_from(id: &Identifier) -> Result<Self, Self::Error> {
+        let normalized = id.normalize()?;
+        let Some(range) = normalized.ranges.first() else {
+            return Err(SmittenError::WholeSequence(id.to_string()));
+        };
+        let start0 = Identifier::zero_based_start(range.start, range.end)?;
+        let interval = Interval::new(normalized.sequence_id.clone(), start0 as u64..range.end as u64);
+        Ok((normalized.assembly_id.unwrap_or_default(), interval))
+    }
+}
+
+impl TryFrom<Identifier> for (String, Interval) {
+    type Error = SmittenError;
+
+    fn try_from(id: Identifier) -> Result<Self, Self::Error> {
+        (&id).try_into()
+    }
+}
+
+impl Identifier {
+    /// Builds a single-range identifier from a `bio_types` interval, the
+    /// inverse of the `(String, Interval)` conversion.  An empty `assembly`
+    /// means the identifier has no assembly.
+    pub fn from_bio_interval(assembly: &str, interval: &Interval, strand: BioStrand) -> Result<Self, SmittenError> {
+        let strand = Strand::try_from(strand)?;
+        let range = interval.range();
+        let overflow = || SmittenError::CoordinateOverflow(format!("interval {:?} does not fit in a usize", range));
+        let start = usize::try_from(range.start).ok().and_then(|start| start.checked_add(1)).ok_or_else(overflow)?;
+        let end = usize::try_from(range.end).map_err(|_| overflow())?;
+        if start > end {
+            return Err(SmittenError::DecreasingRange { start, end });
+        }
+
+        let assembly_id = (!assembly.is_empty()).then(|| assembly.to_string());
+        Identifier::new(assembly_id, interval.contig().to_string(), vec![Range { start, end, orientation: strand }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bio_interval_roundtrip() {
+        let test_cases = vec![
+            ("hg38:chr1:100-200_+", "hg38", "chr1", 99..200, BioStrand::Forward),
+            ("chr1:100-200_-", "", "chr1", 99..200, BioStrand::Reverse),
+            ("hg38:chr1:100-200_+:10-50_-", "hg38", "chr1", 108..149, BioStrand::Reverse),
+        ];
+
+        for (id, assembly, contig, range, strand) in test_cases {
+            let parsed = Identifier::from_v2(id).unwrap();
+            let normalized = parsed.normalize().unwrap();
+            let (got_assembly, interval) = <(String, Interval)>::try_from(&parsed).unwrap();
+            assert_eq!(got_assembly, assembly, "Assembly mismatch for ID: {}", id);
+            assert_eq!(interval.contig(), contig, "Contig mismatch for ID: {}", id);
+            assert_eq!(interval.range(), range, "Interval mismatch for ID: {}", id);
+            assert_eq!(BioStrand::from(normalized.ranges[0].orientation), strand, "Strand mismatch for ID: {}", id);
+
+            let back = Identifier::from_bio_interval(&got_assembly, &interval, strand).unwrap();
+            assert_eq!(back, normalized, "Round trip mismatch for ID: {}", id);
+        }
+
+        let whole = Identifier::from_v2("chr1").unwrap();
+        assert!(matches!(<(String, Interval)>::try_from(whole), Err(SmittenError::WholeSequence(_))));
+        let zero_start = Identifier::from_v2("chr1:0-10_+").unwrap();
+        assert_eq!(<(String, Interval)>::try_from(zero_start), Err(SmittenError::ZeroCoordinate { start: 0, end: 10 }));
+        assert_eq!(Strand::try_from(BioStrand::Unknown), Err(SmittenError::InvalidOrientation('.')));
+        let interval = Interval::new("chr1".to_string(), 0..10);
+        assert!(Identifier::from_bio_interval("", &interval, BioStrand::Unknown).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::IDVersion;
+use alloc::string::String;
+
+/// Errors produced while converting, parsing, or normalizing Smitten identifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SmittenError {
+    /// The identifier contains a space or a line termination character, the
+    /// first of which is at byte offset `position`.
+    WhitespaceInIdentifier { id: String, position: usize },
+    /// A range was given with `start > end` in a format that requires increasing order.
+    DecreasingRange { start: usize, end: usize },
+    /// A range contains a zero coordinate, which is invalid in a one-based system.
+    ZeroCoordinate { start: usize, end: usize },
+    /// A sub-range lies outside the bounds of its parent range.
+    SubrangeOutOfBounds { start: usize, end: usize, parent_len: usize },
+    /// The range at `level` (0 is the outermost) ends beyond the length of its parent range.
+    ChainOutOfBounds { level: usize, start: usize, end: usize, parent_len: usize },
+    /// A coordinate, or arithmetic on coordinates, does not fit in a `usize`.
+    CoordinateOverflow(String),
+    /// The assembly/sequence portion has extra ':'s or no sequence identifier.
+    /// `position` is the byte offset of the offending ':'.
+    InvalidAssemblyStructure { id: String, position: usize },
+    /// The identifier does not have a sequence identifier.
+    EmptySequenceId(String),
+    /// The identifier has an assembly separator but an empty assembly identifier.
+    EmptyAssemblyId(String),
+    /// The identifier contains a character outside the V2 character set.
+    InvalidCharacter { id: String, character: char },
+    /// A range suffix contains text that is not a sequence of V2 ranges.
+    InvalidRangeSuffix(String),
+    /// The identifier was inferred as a different format version than the one requested.
+    VersionMismatch { id: String, expected: IDVersion, found: IDVersion },
+    /// The identifier cannot be faithfully represented in the requested format version.
+    UnrepresentableInVersion { id: String, version: IDVersion },
+    /// The operation requires a coordinate range, but the identifier refers to a whole sequence.
+    WholeSequence(String),
+    /// A range orientation other than '+' or '-' was encountered.
+    InvalidOrientation(char),
+    /// A range separator that would be ambiguous with the range syntax was requested.
+    InvalidSeparator(char),
+    /// A range index at or beyond the number of ranges in the identifier was given.
+    RangeIndexOutOfBounds { index: usize, depth: usize },
+    /// The identifier has more chained ranges than the allowed maximum depth.
+    RangeDepthExceeded(usize),
+    /// Two identifiers were compared but do not share the same root sequence.
+    RootMismatch { left: String, right: String },
+    /// A window size or step of zero was requested.
+    InvalidWindow { size: usize, step: usize },
+    /// The sequence identifier is not in the supplied sequence lengths.
+    UnknownSequence(String),
+    /// An operation over a collection of identifiers was given none.
+    NoIdentifiers,
+    /// An I/O error occurred while reading identifiers.
+    Io(String),
+}
+
+impl core::fmt::Display for SmittenError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SmittenError::WhitespaceInIdentifier { id, position } => write!(
+                f,
+                "Identifier '{}' contains a space or a line termination character at byte {}!",
+                id, position
+            ),
+            SmittenError::DecreasingRange { start, end } => write!(
+                f,
+                "Range {}-{} must have increasing range order!",
+                start, end
+            ),
+            SmittenError::ZeroCoordinate { start, end } => write!(
+                f,
+                "Invalid range {}-{} in a one-based fully-closed coordinate system.",
+                start, end
+            ),
+            SmittenError::SubrangeOutOfBounds { start, end, parent_len } => write!(
+                f,
+                "Sequence sub-range {}-{} is outside the bounds of the parent range length {}.",
+                start, end, parent_len
+            ),
+            SmittenError::ChainOutOfBounds { level, start, end, parent_len } => write!(
+                f,
+                "Range {}-{} at level {} ends beyond the parent range length {}.",
+                start, end, level, parent_len
+            ),
+            SmittenError::CoordinateOverflow(detail) => write!(
+                f,
+                "Coordinate overflow: {}",
+                detail
+            ),
+            SmittenError::InvalidAssemblyStructure { id, position } => write!(
+                f,
+                "Identifier '{}' contains an invalid assembly+sequence structure at byte {}, extra ':'s or no sequence identifier.",
+                id, position
+            ),
+            SmittenError::EmptySequenceId(id) => write!(
+                f,
+                "Identifier '{}' does not have a sequence identifier!",
+                id
+            ),
+            SmittenError::EmptyAssemblyId(id) => write!(
+                f,
+                "Identifier '{}' has an empty assembly identifier!",
+                id
+            ),
+            SmittenError::InvalidCharacter { id, character } => write!(
+                f,
+                "Identifier '{}' contains the character {:?} which is not allowed in the V2 format.",
+                id, character
+            ),
+            SmittenError::InvalidRangeSuffix(suffix) => write!(
+                f,
+                "Range suffix '{}' is not a sequence of ':start-end_orient' ranges.",
+                suffix
+            ),
+            SmittenError::VersionMismatch { id, expected, found } => write!(
+                f,
+                "Identifier '{}' is in the {} format, expected {}.",
+                id, found, expected
+            ),
+            SmittenError::UnrepresentableInVersion { id, version } => write!(
+                f,
+                "Identifier '{}' cannot be faithfully represented in the {} format.",
+                id, version
+            ),
+            SmittenError::WholeSequence(id) => write!(
+                f,
+                "Identifier '{}' refers to a whole sequence and has no coordinate range.",
+                id
+            ),
+            SmittenError::InvalidOrientation(c) => write!(
+                f,
+                "Invalid range orientation '{}', expected '+' or '-'.",
+                c
+            ),
+            SmittenError::InvalidSeparator(sep) => write!(
+                f,
+                "Invalid range separator {:?}, it must not be a digit, whitespace, '-', '+' or '_'.",
+                sep
+            ),
+            SmittenError::RangeIndexOutOfBounds { index, depth } => write!(
+                f,
+                "Range index {} is out of bounds for an identifier with {} ranges.",
+                index, depth
+            ),
+            SmittenError::RangeDepthExceeded(max_depth) => write!(
+                f,
+                "Identifier exceeds the maximum range depth of {}.",
+                max_depth
+            ),
+            SmittenError::RootMismatch { left, right } => write!(
+                f,
+                "Identifiers '{}' and '{}' do not share the same root sequence.",
+                left, right
+            ),
+            SmittenError::InvalidWindow { size, step } => write!(
+                f,
+                "Invalid window size {} with step {}, both must be greater than zero.",
+                size, step
+            ),
+            SmittenError::UnknownSequence(sequence_id) => write!(
+                f,
+                "Sequence '{}' has no known length.",
+                sequence_id
+            ),
+            SmittenError::NoIdentifiers => write!(f, "No identifiers were given."),
+            SmittenError::Io(msg) => write!(f, "I/O error while reading identifiers: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SmittenError {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for SmittenError {
+    fn from(err: std::io::Error) -> Self {
+        SmittenError::Io(err.to_string())
+    }
+}
@@ -11,7 +11,7 @@ format (V2).
 Here is a quick overview of how the library is used:
 
  ```rust
- use smitten::{Identifier, IDVersion};
+ use smitten::{Identifier, IDVersion, Strand};
 
  // Parse a current Smitten identifier
  let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+").unwrap();
@@ -20,7 +20,7 @@ Here is a quick overview of how the library is used:
  assert_eq!(parsed_id.ranges.len(), 1);
  assert_eq!(parsed_id.ranges[0].start, 100);
  assert_eq!(parsed_id.ranges[0].end, 200);
- assert_eq!(parsed_id.ranges[0].orientation, '+');
+ assert_eq!(parsed_id.ranges[0].orientation, Strand::Forward);
 
  // Parse an unknown Smitten identifier and convert to V2
  let (parsed_id, inferred_version) = Identifier::from_unknown_format("chr1_100_200", false, false).unwrap();
@@ -90,38 +90,305 @@ sequence_identifier = 1*(ALPHA / DIGIT / special_chars )
 start_position = 1*(DIGIT) ; 1-based sequence position
 end_position = 1*(DIGIT) ; 1-based sequence position, fully closed coordinates
 ```
+
+# Features
+
+* `std` (default): implements `std::error::Error` for `SmittenError` and
+  enables the APIs that need `std`, `Identifier::from_reader` and
+  `group_by_root`.  Without it the crate is `no_std` and only requires
+  `alloc`.
 */
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
 
+mod error;
+mod macros;
+#[cfg(feature = "bio-types")]
+mod bio;
+#[cfg(feature = "serde")]
+pub mod v2_string;
+
+pub use error::SmittenError;
+#[doc(hidden)]
+pub use macros::validate_v2_literal;
+#[cfg(feature = "serde")]
+pub use v2_string::V2String;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use once_cell::race::OnceBox;
 use regex::Regex;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+// The V2 special_chars, excluding ':' which separates the assembly and sequence identifiers
+const V2_SPECIAL_CHARS: &str = "/?#[]@!$&'()*+,;=~|^\"><.%-_";
+
+/// The default maximum number of chained ranges accepted when parsing an identifier.
+pub const MAX_RANGE_DEPTH: usize = 64;
+
+// A regex compiled once on first use.  `OnceBox` is used rather than
+// `std::sync::LazyLock` so that the statics also work without `std`.
+struct LazyRegex {
+    pattern: &'static str,
+    regex: OnceBox<Regex>,
+}
+
+impl LazyRegex {
+    const fn new(pattern: &'static str) -> Self {
+        LazyRegex { pattern, regex: OnceBox::new() }
+    }
+}
+
+impl core::ops::Deref for LazyRegex {
+    type Target = Regex;
+
+    fn deref(&self) -> &Regex {
+        self.regex.get_or_init(|| Box::new(Regex::new(self.pattern).unwrap()))
+    }
+}
+
+// Range suffix patterns.  Coordinates are ASCII `[0-9]`, since `\d` would
+// also match other Unicode digits that `usize` parsing rejects
+//                                                 1   23     4       5     6       78  9
+static CONVERT_ID_RE: LazyRegex = LazyRegex::new(r"(.*)(([:_])([0-9]+)([-_])([0-9]+)((_)([R+\-]))?)$");
+
+static PARSE_ID_RE: LazyRegex = LazyRegex::new(r"(.*)(([:])([0-9]+)([-])([0-9]+)((_)([+\-]))?)$");
+
+// A V2 range suffix whose coordinates may contain thousands separators and
+// whose orientation may be written as F/R
+static LENIENT_RANGE_RE: LazyRegex =
+    LazyRegex::new(r"(.*):([0-9]{1,3}(?:,[0-9]{3})+|[0-9]+)-([0-9]{1,3}(?:,[0-9]{3})+|[0-9]+)((_)([+\-FR]))?$");
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub enum IDVersion {
+    /// Range-like text was present but not recognized as any version.
+    #[default]
     Undefined,
+    /// A valid sequence identifier without any ranges, e.g. `hg38:chr1`.
+    WholeSequence,
     Mixed,
     V0,
     V1,
     V2,
 }
 
-#[derive(Debug, Clone)]
+/// The coordinate system used for the ranges of an input identifier.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum CoordSystem {
+    /// One-based fully-closed coordinates, the native Smitten convention
+    #[default]
+    OneBasedFullyClosed,
+    /// Zero-based half-open coordinates (e.g. BED)
+    ZeroBasedHalfOpen,
+}
+
+/// The strand orientation of a range, rendered as `+` or `-`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
+impl Strand {
+    /// Returns the opposite strand.
+    pub fn complement(self) -> Self {
+        match self {
+            Strand::Forward => Strand::Reverse,
+            Strand::Reverse => Strand::Forward,
+        }
+    }
+
+    /// Returns the strand of a child range with orientation `child` placed
+    /// within a parent range on this strand: `Forward` if the two strands
+    /// are equal, `Reverse` otherwise.
+    pub fn compose(self, child: Strand) -> Self {
+        if self == child {
+            Strand::Forward
+        } else {
+            Strand::Reverse
+        }
+    }
+
+    /// Returns the V2 orientation character, `+` or `-`.
+    pub fn as_char(self) -> char {
+        match self {
+            Strand::Forward => '+',
+            Strand::Reverse => '-',
+        }
+    }
+}
+
+/// A range on its parent sequence.
+///
+/// `orientation` was a `char` (`'+'` or `'-'`) in earlier releases.  Use
+/// `orientation_char()` where the character is still needed, and
+/// `"+".parse::<Strand>()` or `Strand::Forward`/`Strand::Reverse` when
+/// constructing ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Range {
     pub start: usize,
     pub end: usize,
-    pub orientation: char, // '+' or '-'
+    pub orientation: Strand,
 }
 
+impl Range {
+    /// Returns the orientation as a `+` or `-` character.
+    pub fn orientation_char(&self) -> char {
+        self.orientation.as_char()
+    }
+}
+
+/// A parsed Smitten identifier.
+///
+/// Equality and hashing compare `assembly_id`, `sequence_id`, `ranges` and
+/// `whole_sequence_strand` only.  The `inferred_version` records how the
+/// identifier was parsed rather than what it refers to, and is ignored, as
+/// is the `annotation`.
+///
+/// `whole_sequence_strand` is only set by `from_v2_with_whole_strand` for
+/// rangeless identifiers such as `chr1_-`, and is `None` (forward or
+/// unspecified) otherwise.  It is rendered, compared and hashed only while
+/// `ranges` is empty, and `project_child` clears it.
+///
+/// `annotation` is free-form metadata set with `with_annotation`, e.g. an
+/// AGP component type such as `W` or `N`.  It is carried through `clone`
+/// and normalization, but is not rendered and is ignored for equality.
 #[derive(Debug, Clone)]
 pub struct Identifier {
     pub assembly_id: Option<String>,
     pub sequence_id: String,
     pub ranges: Vec<Range>,
     pub inferred_version: IDVersion,
+    pub whole_sequence_strand: Option<Strand>,
+    pub annotation: Option<String>,
+}
+
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.assembly_id == other.assembly_id
+            && self.sequence_id == other.sequence_id
+            && self.ranges == other.ranges
+            && self.rangeless_strand() == other.rangeless_strand()
+    }
+}
+
+impl Eq for Identifier {}
+
+/// Identifiers are ordered in genomic order:
+///
+/// 1. `assembly_id`, with `None` sorting before any `Some`
+/// 2. `sequence_id`, lexicographically
+/// 3. the start of the `absolute_range`, with whole-sequence identifiers first
+/// 4. the end of the `absolute_range`
+/// 5. the orientation of the `absolute_range` (`+` before `-`)
+///
+/// Identifiers whose chain cannot be collapsed sort as whole-sequence
+/// identifiers.  Identifiers that collapse to the same absolute range through different
+/// chains are finally ordered by their raw `ranges` so that the ordering
+/// agrees with `Eq`.
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.assembly_id
+            .cmp(&other.assembly_id)
+            .then_with(|| self.sequence_id.cmp(&other.sequence_id))
+            .then_with(|| {
+                let span = self.absolute_range().ok().flatten();
+                span.cmp(&other.absolute_range().ok().flatten())
+            })
+            .then_with(|| self.ranges.cmp(&other.ranges))
+            .then_with(|| self.rangeless_strand().cmp(&other.rangeless_strand()))
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl core::hash::Hash for Identifier {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.assembly_id.hash(state);
+        self.sequence_id.hash(state);
+        self.ranges.hash(state);
+        self.rangeless_strand().hash(state);
+    }
 }
 
+// An absolute (start, end, orientation) range on a root sequence
+type Span = (usize, usize, Strand);
+
+/// The result of parsing an identifier of unknown format along with its inferred version.
+pub type ParseResult = Result<(Identifier, IDVersion), SmittenError>;
+
 // Define the API
 impl Identifier {
     /// Creates an `Identifier` from an identifier of unknown format (V0 or V1), converting it to V2.
-    pub fn from_unknown_format(id: &str, zbho: bool, allow_mixed: bool) -> Result<(Self, IDVersion), String> {
+    pub fn from_unknown_format(id: &str, zbho: bool, allow_mixed: bool) -> Result<(Self, IDVersion), SmittenError> {
+        let coord_system = if zbho { CoordSystem::ZeroBasedHalfOpen } else { CoordSystem::OneBasedFullyClosed };
+        Identifier::from_unknown_format_cs(id, coord_system, allow_mixed)
+    }
+
+    /// Creates an `Identifier` like `from_unknown_format`, also returning
+    /// human-readable notes on how ambiguous input was resolved, e.g. when
+    /// range-like text was kept as part of the sequence name.  Mixed-version
+    /// identifiers are not allowed.
+    pub fn from_unknown_format_verbose(id: &str, zbho: bool) -> Result<(Self, IDVersion, Vec<String>), SmittenError> {
+        let (parsed_id, version) = Identifier::from_unknown_format(id, zbho, false)?;
+        let notes = Identifier::conversion_notes(&parsed_id, &version);
+        Ok((parsed_id, version, notes))
+    }
+
+    /// Parses the identifier from a FASTA header line such as
+    /// `>hg38:chr1:100-200_+ some description`.  The leading `>` is optional.
+    /// The first whitespace-delimited token is parsed with
+    /// `from_unknown_format`, and any remaining text is returned as the
+    /// description.
+    pub fn from_fasta_header(header: &str, zbho: bool) -> Result<(Self, Option<String>), SmittenError> {
+        let header = header.strip_prefix('>').unwrap_or(header).trim_start();
+        let (id, description) = match header.split_once(char::is_whitespace) {
+            Some((id, rest)) => (id, rest.trim()),
+            None => (header, ""),
+        };
+        let (parsed_id, _) = Identifier::from_unknown_format(id, zbho, false)?;
+        let description = (!description.is_empty()).then(|| description.to_string());
+        Ok((parsed_id, description))
+    }
+
+    /// Parses the identifier at the start of an annotated line such as
+    /// `chr1:100-200_+\tgene=FOXP2`, splitting on the first ASCII whitespace
+    /// character.  Everything after that character is returned verbatim, or
+    /// `None` if the input contains no whitespace.
+    pub fn parse_with_trailing(input: &str, zbho: bool) -> Result<(Self, Option<String>), SmittenError> {
+        let (id, trailing) = match input.split_once(|c: char| c.is_ascii_whitespace()) {
+            Some((id, rest)) => (id, Some(rest.to_string())),
+            None => (input, None),
+        };
+        let (parsed_id, _) = Identifier::from_unknown_format(id, zbho, false)?;
+        Ok((parsed_id, trailing))
+    }
+
+    /// Creates an `Identifier` like `from_unknown_format` after trimming
+    /// leading and trailing ASCII whitespace.  Internal whitespace is still
+    /// rejected, and mixed-version identifiers are not allowed.
+    pub fn from_unknown_format_trimmed(id: &str, zbho: bool) -> Result<(Self, IDVersion), SmittenError> {
+        Identifier::from_unknown_format(id.trim_matches(|c: char| c.is_ascii_whitespace()), zbho, false)
+    }
+
+    /// Creates an `Identifier` from an identifier of unknown format like
+    /// `from_unknown_format`, with the input coordinate system given explicitly.
+    pub fn from_unknown_format_cs(id: &str, coord_system: CoordSystem, allow_mixed: bool) -> Result<(Self, IDVersion), SmittenError> {
+        let zbho = coord_system == CoordSystem::ZeroBasedHalfOpen;
+
         // Attempt to convert to V2 format
         let (v2_id, inferred_version) = Identifier::convert_id(id, zbho, allow_mixed)?;
 
@@ -132,545 +399,4122 @@ impl Identifier {
         Ok((identifier, inferred_version))
     }
 
-    // By providing these public APIs, we can provide more focused 
-    // converters in the future for improved error handling.
-    /// Other constructors for specific versions remain the same
-    pub fn from_v0(id: &str) -> Result<Self, String> {
-        let (v2_id, _) = Identifier::convert_id(id, false, false)?;
-        Identifier::parse_id(&v2_id)
+    /// Creates an `Identifier` from a V0 identifier, e.g. `chr1_100_200_R`.
+    /// Identifiers inferred as any other version are rejected, except
+    /// rangeless ones which are valid in every version.
+    pub fn from_v0(id: &str) -> Result<Self, SmittenError> {
+        Identifier::from_version(id, IDVersion::V0)
     }
 
-    pub fn from_v1(id: &str) -> Result<Self, String> {
-        let (v2_id, _) = Identifier::convert_id(id, false, false)?;
-        Identifier::parse_id(&v2_id)
+    /// Creates an `Identifier` from a V1 identifier, e.g. `chr1:200-100`.
+    /// Identifiers inferred as any other version are rejected, except
+    /// rangeless ones which are valid in every version.
+    pub fn from_v1(id: &str) -> Result<Self, SmittenError> {
+        Identifier::from_version(id, IDVersion::V1)
     }
 
-    pub fn from_v2(id: &str) -> Result<Self, String> {
+    pub fn from_v2(id: &str) -> Result<Self, SmittenError> {
         Identifier::parse_id(id)
     }
 
-    pub fn normalize(&self) -> Result<Self, String> {
-        let normalized_id_str = self.normalize_id()?;
-        Identifier::parse_id(&normalized_id_str)
+    /// Parses a V2 identifier from a dialect that uses `sep` instead of ':'
+    /// before each range, e.g. `hg38:chr1|100-200_+` with `sep = '|'`.  The
+    /// assembly is still separated from the sequence by ':'.
+    ///
+    /// Digits, whitespace, '-', '+' and '_' are ambiguous with the range
+    /// syntax and are rejected as separators.
+    pub fn from_v2_with_separator(id: &str, sep: char) -> Result<Self, SmittenError> {
+        if sep.is_ascii_digit() || sep.is_whitespace() || matches!(sep, '-' | '+' | '_') {
+            return Err(SmittenError::InvalidSeparator(sep));
+        }
+        let re = Regex::new(&format!(
+            r"(.*)(({})([0-9]+)([-])([0-9]+)((_)([+\-]))?)$",
+            regex::escape(&sep.to_string())
+        ))
+        .expect("escaped separator is a valid regex");
+        Identifier::parse_id_with(id, &re, MAX_RANGE_DEPTH)
     }
-}
 
+    /// Parses a V2 identifier like `from_v2`, additionally recognizing a
+    /// trailing `_+` or `_-` on a rangeless identifier as the strand of the
+    /// whole sequence, e.g. `chr1_-` for the entire reverse strand of `chr1`.
+    /// The marker is recorded in `whole_sequence_strand` rather than as a
+    /// range, since a range would need coordinates.
+    pub fn from_v2_with_whole_strand(id: &str) -> Result<Self, SmittenError> {
+        let mut parsed_id = Identifier::parse_id(id)?;
+        if parsed_id.ranges.is_empty() {
+            let marker = parsed_id.sequence_id.rsplit_once('_').filter(|(name, _)| !name.is_empty());
+            if let Some((name, Ok(strand))) = marker.map(|(name, token)| (name, token.parse::<Strand>())) {
+                parsed_id.sequence_id = name.to_string();
+                parsed_id.whole_sequence_strand = Some(strand);
+            }
+        }
+        Ok(parsed_id)
+    }
 
-impl std::fmt::Display for IDVersion {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let version_str = match self {
-            IDVersion::Undefined => "Undefined",
-            IDVersion::Mixed => "Mixed",
-            IDVersion::V0 => "V0",
-            IDVersion::V1 => "V1",
-            IDVersion::V2 => "V2",
-        };
-    write!(f, "{}", version_str)
+    /// Converts `id` with `from_unknown_format`, renders the result as V2,
+    /// and re-parses it with `from_v2`, returning whether the two parses are
+    /// equal.  Conversion and re-parse failures are returned as errors.
+    pub fn roundtrip_check(id: &str, zbho: bool) -> Result<bool, SmittenError> {
+        let (converted_id, _) = Identifier::from_unknown_format(id, zbho, false)?;
+        let reparsed_id = Identifier::from_v2(&converted_id.to_string())?;
+        Ok(converted_id == reparsed_id)
     }
-}
 
-impl std::fmt::Display for Identifier {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut v2_id = String::new();
+    /// Parses a V2 identifier like `from_v2` into a view borrowing from `id`,
+    /// without allocating.  Both parsers only read ASCII digits as
+    /// coordinates, and return the same error for the same bad input.
+    pub fn parse_id_ref(id: &str) -> Result<IdentifierRef<'_>, SmittenError> {
+        Identifier::check_whitespace(id)?;
 
-        if let Some(assembly) = &self.assembly_id {
-            v2_id.push_str(&format!("{}:", assembly));
+        let mut prefix = id;
+        let mut depth = 0;
+        while let Some((rest, token)) = Identifier::split_last_range(prefix) {
+            let range = Identifier::parse_range_token(token)?;
+            if range.start > range.end {
+                return Err(SmittenError::DecreasingRange { start: range.start, end: range.end });
+            }
+            if depth >= MAX_RANGE_DEPTH {
+                return Err(SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH));
+            }
+            depth += 1;
+            prefix = rest;
         }
+        let ranges_suffix = &id[prefix.len()..];
 
-        v2_id.push_str(&self.sequence_id);
-
-        if !self.ranges.is_empty() {
-            for range in &self.ranges {
-                v2_id.push_str(&format!(":{}-{}_{}", range.start, range.end, range.orientation));
+        let (assembly_id, sequence_id) = match prefix.split_once(':') {
+            Some((_, sequence)) if sequence.contains(':') => {
+                return Err(Identifier::assembly_structure_error(id, prefix));
             }
+            Some(("", _)) => return Err(SmittenError::EmptyAssemblyId(id.to_string())),
+            Some((assembly, sequence)) => (Some(assembly), sequence),
+            None => (None, prefix),
+        };
+        if sequence_id.is_empty() {
+            return Err(SmittenError::EmptySequenceId(id.to_string()));
         }
 
-        write!(f, "{}", v2_id)
+        Ok(IdentifierRef { assembly_id, sequence_id, ranges_suffix })
     }
-}
 
-impl Identifier {
-    /// Converts a Smitten format identifier in V0, V1, or V2 to V2 format.
-    ///
-    /// # Arguments
-    ///
-    /// * `id` - Sequence identifier in V0, V1, or V2 format
-    /// * `zbho` - Boolean flag; if true, treats coordinate ranges as zero-based half-open (ZBHO)
-    /// * `allow_mixed` - Boolean flag; if true, allows mixed V0/V1/V2 identifiers
+    /// Returns `true` if `id` is accepted by `from_v2`.  Checked with the
+    /// non-allocating `parse_id_ref`, discarding the view and the error.
+    pub fn is_valid_v2(id: &str) -> bool {
+        Identifier::parse_id_ref(id).is_ok()
+    }
+
+    /// Normalizes a chained identifier to a single range on the root
+    /// sequence, returning a new V2 `Identifier`.
     ///
-    /// # Returns
+    /// Each child is placed within its parent from the parent's start on `+`
+    /// and from the parent's end on `-`, and the orientations combine as:
     ///
-    /// Returns a tuple `(String, IDVersion)` with the V2 equivalent identifier and inferred version.
+    /// | parent | child | result |
+    /// |--------|-------|--------|
+    /// | `+`    | `+`   | `+`    |
+    /// | `+`    | `-`   | `-`    |
+    /// | `-`    | `+`   | `-`    |
+    /// | `-`    | `-`   | `+`    |
     ///
-    fn convert_id(id: &str, zbho: bool, allow_mixed: bool) -> Result<(String, IDVersion), String> {
-        if id.contains(|c: char| c.is_whitespace() || c == '\n' || c == '\r') {
-            return Err(format!(
-                "convertID: Identifier '{}' contains a space or a line termination character!",
-                id
-            ));
-        }
-
-        //                      1 2  3     4    5     6  7 8    9
-        let re = Regex::new(r"(.*)(([:_])(\d+)([-_])(\d+)((_)([R+\-]))?)$").unwrap();
-        let mut inferred_fmt = None;
-        let mut sequence_id = id.to_string();
-        let mut ranges = Vec::new();
+    /// so a chain of any depth normalizes to `-` exactly when it contains an
+    /// odd number of `-` ranges.
+    pub fn normalize(&self) -> Result<Self, SmittenError> {
+        let mut normalized_id = self.clone();
+        normalized_id.normalize_mut()?;
+        normalized_id.inferred_version = IDVersion::V2;
+        Ok(normalized_id)
+    }
 
-        // Iterate over matches and parse ranges
-        while let Some(captures) = re.captures(&sequence_id) {
-            let start = captures[4].parse::<usize>().unwrap();
-            let end = captures[6].parse::<usize>().unwrap();
-            let orientation = captures.get(9).map_or('+', |m| {
-                if m.as_str() == "R" { '-' } else { m.as_str().chars().next().unwrap() }
-            });
+    /// Normalizes the identifier in place, replacing its chain of ranges with
+    /// the single `absolute_range`.  Whole-sequence identifiers are left
+    /// unchanged.
+    pub fn normalize_mut(&mut self) -> Result<(), SmittenError> {
+        if let Some((start, end, orientation)) = self.absolute_range()? {
+            self.ranges = vec![Range { start, end, orientation }];
+        }
+        Ok(())
+    }
 
-            let start = if zbho { start + 1 } else { start };
+    /// Returns `normalize()` for chained identifiers, and an unchanged clone
+    /// (keeping its `inferred_version`) for whole-sequence and single-range
+    /// identifiers, which are already normalized.
+    pub fn normalized_or_self(&self) -> Result<Identifier, SmittenError> {
+        if self.ranges.len() <= 1 {
+            return Ok(self.clone());
+        }
+        self.normalize()
+    }
 
-            // Infer format based on separators 
-            let range_fmt = if captures.get(3).map(|s| s.as_str()) == Some(":")
-                && captures.get(5).map(|s| s.as_str()) == Some("-")
-                && ( captures.get(7).map(|s| s.as_str()) == Some("_+") ||
-                     captures.get(7).map(|s| s.as_str()) == Some("_-") )
-            {
-                IDVersion::V2
-            } else if captures.get(3).map(|s| s.as_str()) == Some(":")
-                   && captures.get(5).map(|s| s.as_str()) == Some("-")
-                   && captures.get(7).is_none()
-            {
-                IDVersion::V1
+    /// Returns the length of the innermost range in one-based fully-closed
+    /// coordinates, or `None` if the identifier refers to a whole sequence.
+    ///
+    /// Returns an error for a range such as `0-18446744073709551615`, whose
+    /// length does not fit in a `usize`.
+    pub fn length(&self) -> Result<Option<usize>, SmittenError> {
+        self.ranges.last().map(Identifier::range_length).transpose()
+    }
 
-            // This is a special case of v0 (e.g "chr1:100-200_R") that was in-use by Arian for
-            // awhile...sigh
-            } else if captures.get(3).map(|s| s.as_str()) == Some(":")
-                   && captures.get(5).map(|s| s.as_str()) == Some("-")
-                   && (captures.get(7).is_none() || captures.get(7).map(|s| s.as_str()) == Some("_R") )
-            {
-                IDVersion::V0
+    /// Returns `true` if the identifier refers to an entire sequence, i.e. it
+    /// has no coordinate ranges.
+    pub fn is_whole_sequence(&self) -> bool {
+        self.ranges.is_empty()
+    }
 
-            } else if captures.get(3).map(|s| s.as_str()) == Some("_")
-                   && captures.get(5).map(|s| s.as_str()) == Some("_")
-                   && (captures.get(7).is_none() || captures.get(7).map(|s| s.as_str()) == Some("_R") )
-            {
-                IDVersion::V0
+    /// Renders the identifier in the legacy V1 format, encoding the strand of
+    /// each range purely through coordinate ordering (descending for reverse).
+    ///
+    /// Not every identifier survives this: single-base reverse strand ranges
+    /// read back as forward strand, the `whole_sequence_strand` of a
+    /// rangeless identifier such as `chr1_-` is dropped, and V1 rejects
+    /// chains such as `x:1-2:3-4` whose child lies outside its parent.  The
+    /// rendered string is therefore re-parsed with `from_v1`, and identifiers
+    /// that do not round-trip to the same value are rejected.
+    pub fn to_v1_string(&self) -> Result<String, SmittenError> {
+        let mut v1_id = String::new();
 
-            } else {
-                IDVersion::Undefined
-            };
+        if let Some(assembly) = &self.assembly_id {
+            v1_id.push_str(&format!("{}:", assembly));
+        }
 
-            if range_fmt == IDVersion::Undefined {
-                break;
-            }
+        v1_id.push_str(&self.sequence_id);
 
-            if let Some(ref inferred) = inferred_fmt {
-                if *inferred != range_fmt {
-                    if allow_mixed {
-                        inferred_fmt = Some(IDVersion::Mixed); 
-                    }else {
-                        break;
-                    }
-                }
+        for range in &self.ranges {
+            if range.orientation == Strand::Reverse {
+                v1_id.push_str(&format!(":{}-{}", range.end, range.start));
             } else {
-                inferred_fmt = Some(range_fmt.clone());
+                v1_id.push_str(&format!(":{}-{}", range.start, range.end));
             }
-
-            let (ordered_start, ordered_end, final_orientation) = match range_fmt {
-                IDVersion::V0 | IDVersion::V2 if start > end => {
-                    return Err(format!(
-                        "convertID: V{} identifier '{}' must have increasing range order!",
-                        range_fmt, sequence_id
-                    ));
-                }
-                IDVersion::V1 if start > end => (end, start, '-'),
-                IDVersion::V1 => (start, end, '+'),
-                _ => (start, end, orientation),
-            };
-
-            ranges.push(Range {
-                start: ordered_start,
-                end: ordered_end,
-                orientation: final_orientation,
-            });
-
-            sequence_id = captures[1].to_string();
         }
 
-        if ranges.is_empty() {
-            inferred_fmt = Some(IDVersion::Undefined);
+        match Identifier::from_v1(&v1_id) {
+            Ok(ref parsed_id) if parsed_id == self => Ok(v1_id),
+            _ => Err(SmittenError::UnrepresentableInVersion {
+                id: self.to_string(),
+                version: IDVersion::V1,
+            }),
         }
+    }
 
-        let ids: Vec<&str> = sequence_id.split(':').collect();
-        let (assembly_id, sequence_id) = match (sequence_id.matches(':').count(), ids.len()) {
-            (1, 2) if !ids[0].is_empty() && !ids[1].is_empty() => (Some(ids[0]), ids[1]),
-            (0, 1) if !sequence_id.is_empty() => (None, sequence_id.as_str()),
-            _ => {
-                return Err(format!(
-                    "convertID: Identifier '{}' contains an invalid assembly+sequence structure, extra ':'s or no sequence identifier.",
-                    id
-                ));
-            }
-        };
+    /// Renders the identifier in the legacy V0 format, e.g.
+    /// `chr1_11023_38232_R_100_200`, with a trailing `_R` on reverse strand
+    /// ranges.
+    ///
+    /// V0 uses `_` both as the range separator and as a legal sequence
+    /// identifier character, so a sequence identifier such as `seq_100_200`
+    /// would read back as `seq` with an extra range.  The rendered string is
+    /// therefore re-parsed, and identifiers that do not round-trip to the
+    /// same value are rejected.
+    pub fn to_v0_string(&self) -> Result<String, SmittenError> {
+        let mut v0_id = String::new();
 
-        let mut v2_id = if let Some(assembly) = assembly_id {
-            format!("{}:{}", assembly, sequence_id)
-        } else {
-            sequence_id.to_string()
-        };
+        if let Some(assembly) = &self.assembly_id {
+            v0_id.push_str(&format!("{}:", assembly));
+        }
 
-        let mut current_parent_length = None;
-        for range in ranges.iter().rev() {
-            if range.start == 0 || range.end == 0 {
-                return Err(format!(
-                    "convertID: Invalid range {}-{} in a one-based fully-closed coordinate system.",
-                    range.start, range.end
-                ));
-            }
-            if let Some(parent_len) = current_parent_length {
-                if range.start > parent_len || range.end > parent_len {
-                    return Err(format!(
-                        "convertID: Sequence sub-range {}-{} is outside the bounds of the parent range length {}.",
-                        range.start, range.end, parent_len
-                    ));
-                }
+        v0_id.push_str(&self.sequence_id);
+
+        for range in &self.ranges {
+            v0_id.push_str(&format!("_{}_{}", range.start, range.end));
+            if range.orientation == Strand::Reverse {
+                v0_id.push_str("_R");
             }
-            current_parent_length = Some(range.end - range.start + 1);
-            v2_id.push_str(&format!(":{}-{}_{}", range.start, range.end, range.orientation));
         }
 
-        Ok((v2_id, inferred_fmt.unwrap_or(IDVersion::Undefined)))
+        let round_trip = Identifier::convert_id(&v0_id, false, false)
+            .and_then(|(v2_id, _)| Identifier::parse_id(&v2_id));
+        match round_trip {
+            Ok(ref parsed_id) if parsed_id == self => Ok(v0_id),
+            _ => Err(SmittenError::UnrepresentableInVersion {
+                id: self.to_string(),
+                version: IDVersion::V0,
+            }),
+        }
     }
 
-    /// Parses a Smitten format sequence identifier, returning the individual components.
-    ///
-    /// # Arguments
-    ///
-    /// * `id` - Sequence identifier in V0, V1, or V2 format
-    ///
-    /// # Returns
+    /// Normalizes the identifier and renders it as `(v0, v1, v2)` with
+    /// `to_v0_string`, `to_v1_string` and `Display`, e.g. `chr1_100_200_R`,
+    /// `chr1:200-100` and `chr1:100-200_-`.
     ///
-    /// Returns a `Identifier` struct containing `assembly_id`, `sequence_id`, `ranges`, and inferred `version`.
+    /// Returns an error if normalization fails or the normalized identifier
+    /// cannot be represented in V0 or V1.
+    pub fn to_all_versions(&self) -> Result<(String, String, String), SmittenError> {
+        let normalized_id = self.normalize()?;
+        Ok((normalized_id.to_v0_string()?, normalized_id.to_v1_string()?, normalized_id.to_string()))
+    }
+
+    /// Returns each range, outermost first, as a `(start, end, orientation)`
+    /// tuple in zero-based half-open coordinates (`start - 1`, `end`).
     ///
-    fn parse_id(id: &str) -> Result<Self, String> {
-        let re = Regex::new(r"(.*)(([:])(\d+)([-])(\d+)((_)([+\-]))?)$").unwrap();
-        let mut assembly_id = None;
-        let mut ranges = Vec::new();
-        let mut id_str = id.to_string();
+    /// These tuples are returned rather than an `Identifier` because Smitten
+    /// identifiers are always one-based fully-closed.  A range starting at 0,
+    /// which `from_v2` accepts, has no zero-based start and is an error.
+    pub fn to_bed_coords(&self) -> Result<Vec<(usize, usize, Strand)>, SmittenError> {
+        self.ranges
+            .iter()
+            .map(|range| Ok((Identifier::zero_based_start(range.start, range.end)?, range.end, range.orientation)))
+            .collect()
+    }
 
-        // Remove ranges from the end of the ID string
-        while let Some(captures) = re.captures(&id_str) {
-            let start = captures[4].parse::<usize>().unwrap();
-            let end = captures[6].parse::<usize>().unwrap();
-            let orientation = captures[9].chars().next().unwrap();
+    /// Renders the normalized identifier as a tab-delimited BED6 record:
+    /// `sequence_id, start0, end, name, score, strand`.  The `name` column is
+    /// the full V2 identifier and `score` is always `0`.
+    ///
+    /// Whole-sequence identifiers have no span and are rejected.
+    pub fn to_bed(&self) -> Result<String, SmittenError> {
+        if self.is_whole_sequence() {
+            return Err(SmittenError::WholeSequence(self.to_string()));
+        }
 
-            if start > end {
-                return Err(format!(
-                    "parseID: V2 identifiers '{}' must have increasing range order!",
-                    id
-                ));
-            }
+        let normalized_id = self.normalize()?;
+        let (start0, end, orientation) = normalized_id.to_bed_coords()?[0];
 
-            ranges.push(Range { start, end, orientation });
-            id_str = captures[1].to_string();
-        }
+        Ok(format!(
+            "{}\t{}\t{}\t{}\t0\t{}",
+            normalized_id.sequence_id,
+            start0,
+            end,
+            self,
+            orientation
+        ))
+    }
 
-        let ids: Vec<&str> = id_str.split(':').collect();
-        let sequence_id;
-        if ids.len() == 2 {
-            assembly_id = Some(ids[0].to_string());
-            sequence_id = ids[1].to_string();
-        } else if ids.len() == 1 {
-            sequence_id = ids[0].to_string();
-        } else {
-            return Err(format!(
-                "parseID: Identifier '{}' contains an invalid number of ':' characters.",
-                id
-            ));
-        }
+    /// Runs `from_unknown_format` on each identifier, pairing every input
+    /// string with its result so failures can be reported without aborting
+    /// the batch.  Input order is preserved.
+    pub fn parse_many<I, S>(ids: I, zbho: bool) -> Vec<(String, ParseResult)>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        ids.into_iter()
+            .map(|id| {
+                let id = id.as_ref();
+                (id.to_string(), Identifier::from_unknown_format(id, zbho, false))
+            })
+            .collect()
+    }
 
-        if sequence_id.is_empty() {
-            return Err(format!(
-                "parseID: Identifier '{}' does not have a sequence identifier!",
-                id
-            ));
-        }
+    /// Runs `normalize` on each identifier, returning one result per input
+    /// in the same order so failures can be matched back to their inputs.
+    pub fn normalize_many<I: IntoIterator<Item = Identifier>>(ids: I) -> Vec<Result<Identifier, SmittenError>> {
+        ids.into_iter().map(|id| id.normalize()).collect()
+    }
 
-        Ok(Identifier {
-            assembly_id,
-            sequence_id,
-            ranges: ranges.into_iter().rev().collect(),
-            inferred_version: IDVersion::V2,
-        })
+    /// Lazily parses one identifier per line from `reader` using
+    /// `from_unknown_format`, yielding `(line_number, result)` pairs with
+    /// 1-based line numbers.  Blank and whitespace-only lines are skipped.
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::BufRead>(reader: R, zbho: bool) -> impl Iterator<Item = (usize, Result<Self, SmittenError>)> {
+        reader
+            .lines()
+            .enumerate()
+            .filter_map(move |(idx, line)| {
+                let line_number = idx + 1;
+                match line {
+                    Ok(line) if line.trim().is_empty() => None,
+                    Ok(line) => Some((
+                        line_number,
+                        Identifier::from_unknown_format(&line, zbho, false).map(|(id, _)| id),
+                    )),
+                    Err(err) => Some((line_number, Err(SmittenError::from(err)))),
+                }
+            })
     }
 
-    /// Normalizes a chained sequence identifier in Smitten V2 format to a single normalized range.
-    ///
-    /// # Returns
+    /// Returns the identifier for the same span on the opposite strand.  The
+    /// identifier is first normalized to a single range and then the
+    /// orientation of that range is flipped, leaving start/end unchanged.
+    /// Whole-sequence identifiers are returned as-is.
     ///
-    /// Returns a normalized sequence identifier as a `String`.
+    /// Calling this twice returns an identifier equal to the normalized
+    /// original.
+    pub fn reverse_complement(&self) -> Result<Self, SmittenError> {
+        let mut normalized_id = self.normalize()?;
+        for range in &mut normalized_id.ranges {
+            range.orientation = range.orientation.complement();
+        }
+        Ok(normalized_id)
+    }
+
+    /// Collapses the chain of ranges to a single `(start, end, orientation)`
+    /// on the root sequence, or `None` for whole-sequence identifiers.  The
+    /// returned coordinates are always in increasing order.
     ///
-    fn normalize_id(&self) -> Result<String, String> {
+    /// Returns an error if a child range lies outside its parent or the
+    /// coordinate arithmetic overflows.
+    pub fn absolute_range(&self) -> Result<Option<Span>, SmittenError> {
         if self.ranges.is_empty() {
-            let mut ret_str = String::new();
-            if let Some(assembly) = &self.assembly_id {
-                ret_str.push_str(&format!("{}:", assembly));
-            }
-            ret_str.push_str(&self.sequence_id);
-            return Ok(ret_str);
+            return Ok(None);
         }
+        self.relative_to_level(0).map(Some)
+    }
 
-        let mut start_idx = self.ranges.last().unwrap().start;
-        let mut end_idx = self.ranges.last().unwrap().end;
-        let mut curr_orient = self.ranges.last().unwrap().orientation;
+    /// Lazily yields every position of the normalized range in reading
+    /// order: `start` to `end` on `+`, and `end` down to `start` on `-`.
+    ///
+    /// Whole-sequence identifiers have no known end and return an error.
+    pub fn positions(&self) -> Result<impl Iterator<Item = usize>, SmittenError> {
+        let (start, end, orientation) = self
+            .absolute_range()?
+            .ok_or_else(|| SmittenError::WholeSequence(self.to_string()))?;
+        let reverse = orientation == Strand::Reverse;
+        Ok((0..=end - start).map(move |offset| if reverse { end - offset } else { start + offset }))
+    }
 
-        for range in self.ranges.iter().rev().skip(1) {
-            if range.orientation == '-' {
-                start_idx = range.end - start_idx + 1;
-                end_idx = range.end - end_idx + 1;
-            } else {
-                start_idx = range.start + start_idx - 1;
-                end_idx = range.start + end_idx - 1;
-            }
+    /// Returns the orientation of the normalized identifier, the product of
+    /// the chain's strands, e.g. `-` for `chr1:100-200_+:10-50_-:1-5_+`.  A
+    /// whole-sequence identifier returns its `whole_sequence_strand`, which
+    /// is `None` unless parsed by `from_v2_with_whole_strand`.
+    pub fn normalized_strand(&self) -> Result<Option<Strand>, SmittenError> {
+        match self.absolute_range()? {
+            Some((_, _, orientation)) => Ok(Some(orientation)),
+            None => Ok(self.whole_sequence_strand),
+        }
+    }
 
-            curr_orient = if range.orientation == '-' && curr_orient == '-' {
-                '+'
-            } else if range.orientation != curr_orient {
-                '-'
-            }else {
-                curr_orient
-            };
+    /// Collapses the chain only as far as the range at `level - 1`, returning
+    /// the innermost span in that range's coordinate frame.  Level 0 is the
+    /// root sequence, matching `absolute_range`, and the deepest valid level
+    /// is `depth() - 1`, which returns the innermost range unchanged.  For
+    /// `chr1:100-200_+:10-50_-:1-5_+`, level 1 is `46-50_-` within `100-200`.
+    ///
+    /// Returns an error for a whole-sequence identifier, a level outside the
+    /// chain, or a child range outside its parent.
+    pub fn relative_to_level(&self, level: usize) -> Result<Span, SmittenError> {
+        let Some(last) = self.ranges.last() else {
+            return Err(SmittenError::WholeSequence(self.to_string()));
+        };
+        if level >= self.ranges.len() {
+            return Err(SmittenError::RangeIndexOutOfBounds { index: level, depth: self.ranges.len() });
         }
+        let mut span = (last.start, last.end, last.orientation);
 
-        let mut ret_str = String::new();
-        if let Some(assembly) = &self.assembly_id {
-            ret_str.push_str(&format!("{}:", assembly));
+        for range in self.ranges[level..].iter().rev().skip(1) {
+            span = Identifier::fold_span(range, span)?;
         }
-        ret_str.push_str(&self.sequence_id);
-        ret_str.push(':');
+
+        Ok(span)
+    }
+
+    /// Returns a copy of the identifier with a new innermost child range
+    /// appended.  The child must be in increasing order, one-based, and lie
+    /// within the length of the current innermost range.
+    pub fn project_child(&self, start: usize, end: usize, orientation: Strand) -> Result<Self, SmittenError> {
+        if start > end {
+            return Err(SmittenError::DecreasingRange { start, end });
+        }
+        Identifier::check_range_bounds(start, end, self.length()?)?;
+
+        let mut child_id = self.clone();
+        child_id.ranges.push(Range { start, end, orientation });
+        child_id.whole_sequence_strand = None;
+        Ok(child_id)
+    }
+
+    /// Returns the root sequence of the identifier, i.e. the same
+    /// `assembly_id` and `sequence_id` with all ranges removed.
+    pub fn root(&self) -> Self {
+        Identifier {
+            assembly_id: self.assembly_id.clone(),
+            sequence_id: self.sequence_id.clone(),
+            ranges: Vec::new(),
+            inferred_version: self.inferred_version.clone(),
+            whole_sequence_strand: None,
+            annotation: None,
+        }
+    }
+
+    /// Returns `true` if the normalized spans of the two identifiers share at
+    /// least one position, e.g. `100-200` and `200-300` overlap.  Orientation
+    /// is ignored.  A whole-sequence identifier spans the entire sequence and
+    /// so overlaps any identifier on the same root.
+    ///
+    /// Identifiers on different root sequences are not comparable and
+    /// return an error.
+    pub fn overlaps(&self, other: &Identifier) -> Result<bool, SmittenError> {
+        match self.root_spans(other)? {
+            (Some((start1, end1, _)), Some((start2, end2, _))) => Ok(start1 <= end2 && start2 <= end1),
+            _ => Ok(true),
+        }
+    }
+
+    /// Returns the signed gap between two identifiers on the same root
+    /// sequence.  A positive value is the number of bases strictly between
+    /// disjoint ranges, `0` means the ranges are adjacent (e.g. `100-200` and
+    /// `201-300`), and a negative value is the number of overlapping bases,
+    /// negated.  Orientation is ignored.
+    ///
+    /// Identifiers on different root sequences, or whole-sequence
+    /// identifiers, return an error.
+    pub fn distance(&self, other: &Identifier) -> Result<i64, SmittenError> {
+        match self.root_spans(other)? {
+            (Some((start1, end1, _)), Some((start2, end2, _))) => {
+                let to_i64 = |value: usize| {
+                    i64::try_from(value).map_err(|_| {
+                        SmittenError::CoordinateOverflow(format!("coordinate {} does not fit in an i64", value))
+                    })
+                };
+                Ok(to_i64(start1.max(start2))? - to_i64(end1.min(end2))? - 1)
+            }
+            (None, _) => Err(SmittenError::WholeSequence(self.to_string())),
+            (_, None) => Err(SmittenError::WholeSequence(other.to_string())),
+        }
+    }
+
+    /// Returns `true` if the normalized spans of the two identifiers are
+    /// immediately adjacent, with no gap and no overlap, e.g. `100-200` and
+    /// `201-300`.  Orientation is ignored.  A whole-sequence identifier has
+    /// no neighbours on its own sequence.
+    ///
+    /// Identifiers on different root sequences return an error.
+    pub fn is_adjacent(&self, other: &Identifier) -> Result<bool, SmittenError> {
+        match self.root_spans(other)? {
+            (Some((start1, end1, _)), Some((start2, end2, _))) => {
+                Ok(end1.checked_add(1) == Some(start2) || end2.checked_add(1) == Some(start1))
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Returns the span shared by the two identifiers on their common root
+    /// sequence, or `None` if they are disjoint.  The result is oriented `+`
+    /// unless both inputs normalize to `-`.  A whole-sequence identifier
+    /// spans the entire sequence, so intersecting with one returns the other
+    /// identifier normalized.
+    ///
+    /// Identifiers on different root sequences return an error.
+    pub fn intersect(&self, other: &Identifier) -> Result<Option<Identifier>, SmittenError> {
+        match self.root_spans(other)? {
+            (Some((start1, end1, orient1)), Some((start2, end2, orient2))) => {
+                let start = start1.max(start2);
+                let end = end1.min(end2);
+                if start > end {
+                    return Ok(None);
+                }
+                let orientation = if orient1 == Strand::Reverse && orient2 == Strand::Reverse { Strand::Reverse } else { Strand::Forward };
+                Ok(Some(self.root_with_range(start, end, orientation)))
+            }
+            (Some(_), None) => Ok(Some(self.normalize()?)),
+            (None, _) => Ok(Some(other.normalize()?)),
+        }
+    }
+
+    /// Returns `true` if the normalized span of `other` lies entirely within
+    /// the normalized span of `self`.  Equal spans are contained, and a
+    /// whole-sequence identifier contains any identifier on the same root.
+    /// Orientation is ignored.
+    ///
+    /// Identifiers on different root sequences return an error rather than
+    /// `false`.
+    pub fn contains(&self, other: &Identifier) -> Result<bool, SmittenError> {
+        match self.root_spans(other)? {
+            (Some((start1, end1, _)), Some((start2, end2, _))) => Ok(start1 <= start2 && end2 <= end1),
+            (None, _) => Ok(true),
+            (Some(_), None) => Ok(false),
+        }
+    }
+
+    /// Creates a V2 `Identifier` from already-parsed components, validating
+    /// that the sequence identifier is non-empty and that each range is
+    /// increasing, one-based, and within the length of its parent range.
+    pub fn new(assembly_id: Option<String>, sequence_id: String, ranges: Vec<Range>) -> Result<Self, SmittenError> {
+        if sequence_id.is_empty() {
+            return Err(SmittenError::EmptySequenceId(assembly_id.map_or(String::new(), |a| format!("{}:", a))));
+        }
+        Identifier::check_ranges(&ranges)?;
+
+        Ok(Identifier {
+            assembly_id,
+            sequence_id,
+            ranges,
+            inferred_version: IDVersion::V2,
+            whole_sequence_strand: None,
+            annotation: None,
+        })
+    }
+
+    /// Creates a single-range V2 `Identifier` from zero-based half-open BED
+    /// coordinates `[start0, end)`, the inverse of `to_bed_coords`.  The
+    /// interval must be non-empty.
+    pub fn from_bed(sequence_id: &str, start0: usize, end: usize, strand: Strand) -> Result<Self, SmittenError> {
+        let start = start0.checked_add(1).ok_or_else(|| {
+            SmittenError::CoordinateOverflow(format!("zero-based start {} cannot be converted", start0))
+        })?;
+        if start > end {
+            return Err(SmittenError::DecreasingRange { start, end });
+        }
+        Identifier::new(None, sequence_id.to_string(), vec![Range { start, end, orientation: strand }])
+    }
+
+    /// Infers the format version of an identifier using the same separator
+    /// rules as `from_unknown_format`, without converting or validating the
+    /// ranges.  Whitespace-containing identifiers, and those with more than
+    /// `MAX_RANGE_DEPTH` ranges, are `Undefined`, and rangeless ones are
+    /// `WholeSequence` or `Undefined` as in `from_unknown_format`.
+    pub fn detect_version(id: &str) -> IDVersion {
+        if id.contains(char::is_whitespace) {
+            return IDVersion::Undefined;
+        }
+
+        let mut inferred_fmt = IDVersion::Undefined;
+        let mut sequence_id = id;
+        let mut depth = 0;
+        while let Some(captures) = CONVERT_ID_RE.captures(sequence_id) {
+            if depth >= MAX_RANGE_DEPTH {
+                return IDVersion::Undefined;
+            }
+            depth += 1;
+            let range_fmt = Identifier::infer_range_format(&captures);
+            if range_fmt == IDVersion::Undefined
+                || (inferred_fmt != IDVersion::Undefined && inferred_fmt != range_fmt)
+            {
+                break;
+            }
+            inferred_fmt = range_fmt;
+            sequence_id = captures.get(1).map_or("", |m| m.as_str());
+        }
+
+        if inferred_fmt == IDVersion::Undefined {
+            return Identifier::rangeless_version(id);
+        }
+        inferred_fmt
+    }
+
+    /// Iterates over the ranges from the outermost (relative to the root
+    /// sequence) to the innermost.
+    pub fn ranges_outer_to_inner(&self) -> impl Iterator<Item = &Range> {
+        self.ranges.iter()
+    }
+
+    /// Iterates over the ranges from the innermost to the outermost.
+    pub fn ranges_inner_to_outer(&self) -> impl Iterator<Item = &Range> {
+        self.ranges.iter().rev()
+    }
+
+    /// Returns `true` if both identifiers normalize to the same root sequence,
+    /// absolute start/end, and orientation, even when written as different
+    /// chains.  Unlike `==`, which compares the raw chains.
+    pub fn same_span(&self, other: &Identifier) -> Result<bool, SmittenError> {
+        Ok(self.normalize()? == other.normalize()?)
+    }
+
+    /// Creates a V2 `Identifier` from separately supplied assembly and
+    /// sequence names, parsing only `ranges_suffix` (e.g. `:100-200_+:1-5_-`).
+    /// The names are taken verbatim and may contain ':' characters.
+    ///
+    /// Note that `Display` output for names containing ':' cannot be parsed
+    /// back with `from_v2`.
+    pub fn from_v2_parts(assembly: Option<&str>, sequence: &str, ranges_suffix: &str) -> Result<Self, SmittenError> {
+        let (remainder, ranges) = Identifier::parse_ranges(ranges_suffix, &PARSE_ID_RE, MAX_RANGE_DEPTH)?;
+        if !remainder.is_empty() {
+            return Err(SmittenError::InvalidRangeSuffix(ranges_suffix.to_string()));
+        }
+        Identifier::new(assembly.map(|a| a.to_string()), sequence.to_string(), ranges)
+    }
+
+    /// Returns the length (`end - start + 1`) of every range in outer-to-inner
+    /// order, or an error if a length does not fit in a `usize`.
+    pub fn range_lengths(&self) -> Result<Vec<usize>, SmittenError> {
+        self.ranges.iter().map(Identifier::range_length).collect()
+    }
+
+    /// Folds the innermost range into its parent, replacing the last two
+    /// ranges with the child's position in the parent's frame and leaving any
+    /// outer ranges untouched.  Identifiers with fewer than two ranges are
+    /// returned unchanged.
+    pub fn fold_innermost(&self) -> Result<Self, SmittenError> {
+        let mut folded_id = self.clone();
+        if folded_id.ranges.len() < 2 {
+            return Ok(folded_id);
+        }
+
+        let child = folded_id.ranges.pop().unwrap();
+        let parent = folded_id.ranges.pop().unwrap();
+        let (start, end, orientation) = Identifier::fold_span(&parent, (child.start, child.end, child.orientation))?;
+        folded_id.ranges.push(Range { start, end, orientation });
+        Ok(folded_id)
+    }
+
+    /// Parses a V2 identifier like `from_v2`, additionally requiring every
+    /// character of the assembly and sequence identifiers to be an ALPHA,
+    /// DIGIT, or one of the V2 `special_chars` from the format specification.
+    pub fn from_v2_strict(id: &str) -> Result<Self, SmittenError> {
+        let parsed_id = Identifier::from_v2(id)?;
+
+        let names = parsed_id.assembly_id.iter().chain(core::iter::once(&parsed_id.sequence_id));
+        for name in names {
+            if let Some(character) = name
+                .chars()
+                .find(|&c| !(c.is_ascii_alphanumeric() || V2_SPECIAL_CHARS.contains(c)))
+            {
+                return Err(SmittenError::InvalidCharacter { id: id.to_string(), character });
+            }
+        }
+
+        Ok(parsed_id)
+    }
+
+    /// Returns the outermost range, relative to the named sequence, or `None`
+    /// for whole-sequence identifiers.
+    pub fn outermost_range(&self) -> Option<&Range> {
+        self.ranges.first()
+    }
+
+    /// Returns the innermost range, or `None` for whole-sequence identifiers.
+    pub fn innermost_range(&self) -> Option<&Range> {
+        self.ranges.last()
+    }
+
+    /// Parses a V2 identifier whose coordinates may be written with comma
+    /// thousands separators, e.g. `chr1:1,000-2,000_+` as copied from a genome
+    /// browser, and whose orientations may be written as `F`/`R` instead of
+    /// `+`/`-`, e.g. `chr1:100-200_R`.  Commas are only removed from
+    /// well-formed coordinates in the trailing range suffix; commas elsewhere
+    /// are legal sequence identifier characters and are left untouched.
+    /// `F`/`R` are stored and displayed as the canonical `+`/`-`.
+    ///
+    /// This is ambiguous for names that end in something resembling such a
+    /// range, which is why the strict `from_v2` recognizes neither commas in
+    /// coordinates nor `F`/`R` orientations (`chr1:100-200_R` parses there as
+    /// assembly `chr1` and sequence `100-200_R`).
+    pub fn from_v2_lenient(id: &str) -> Result<Self, SmittenError> {
+        let mut prefix = id;
+        let mut suffixes = Vec::new();
+        while let Some(captures) = LENIENT_RANGE_RE.captures(prefix) {
+            let orientation = match captures.get(6).map(|m| m.as_str()) {
+                Some("+") | Some("F") => "_+",
+                Some("-") | Some("R") => "_-",
+                _ => "",
+            };
+            if suffixes.len() >= MAX_RANGE_DEPTH {
+                return Err(SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH));
+            }
+            suffixes.push(format!(
+                ":{}-{}{}",
+                captures[2].replace(',', ""),
+                captures[3].replace(',', ""),
+                orientation
+            ));
+            prefix = captures.get(1).map_or("", |m| m.as_str());
+        }
+
+        let mut v2_id = prefix.to_string();
+        for suffix in suffixes.iter().rev() {
+            v2_id.push_str(suffix);
+        }
+        Identifier::from_v2(&v2_id)
+    }
+
+    /// Parses a V2 identifier like `from_v2`, first swapping the coordinates
+    /// of any descending range whose orientation is `_-`, e.g.
+    /// `chr1:200-100_-` is read as `chr1:100-200_-`.  The orientation already
+    /// records the reverse strand, so the descending order is redundant.
+    ///
+    /// A descending range oriented `_+`, or with no orientation (which
+    /// defaults to `+`), contradicts itself and is still rejected with
+    /// `DecreasingRange`.  Ascending ranges are left as they are.
+    pub fn from_v2_autoorient(id: &str) -> Result<Self, SmittenError> {
+        let mut prefix = id;
+        let mut suffixes = Vec::new();
+        while let Some(captures) = PARSE_ID_RE.captures(prefix) {
+            let start = Identifier::parse_coordinate(&captures[4])?;
+            let end = Identifier::parse_coordinate(&captures[6])?;
+            let orientation = captures.get(7).map_or("", |m| m.as_str());
+            if suffixes.len() >= MAX_RANGE_DEPTH {
+                return Err(SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH));
+            }
+            if start > end && orientation == "_-" {
+                suffixes.push(format!(":{}-{}{}", end, start, orientation));
+            } else {
+                suffixes.push(captures[2].to_string());
+            }
+            prefix = captures.get(1).map_or("", |m| m.as_str());
+        }
+
+        let mut v2_id = prefix.to_string();
+        for suffix in suffixes.iter().rev() {
+            v2_id.push_str(suffix);
+        }
+        Identifier::from_v2(&v2_id)
+    }
+
+    /// Returns a deterministic V2 string for the span the identifier refers
+    /// to: `[assembly:]sequence:start-end_orient`, or `[assembly:]sequence`
+    /// for whole-sequence identifiers.  Identifiers for which `same_span` is
+    /// true always have identical canonical strings.
+    pub fn canonical(&self) -> Result<String, SmittenError> {
+        Ok(self.normalize()?.to_string())
+    }
+
+    /// Returns the GFF3/GTF `(seqid, start, end, strand)` fields of the
+    /// normalized identifier in one-based fully-closed coordinates.  The
+    /// assembly is dropped since GFF has no assembly column.
+    ///
+    /// Whole-sequence identifiers have no coordinates and are rejected.
+    pub fn to_gff_fields(&self) -> Result<(String, usize, usize, Strand), SmittenError> {
+        match self.absolute_range()? {
+            Some((start, end, orientation)) => Ok((self.sequence_id.clone(), start, end, orientation)),
+            None => Err(SmittenError::WholeSequence(self.to_string())),
+        }
+    }
+
+    /// Returns a copy of the identifier with `delta` added to the start and
+    /// end of the outermost range.  Inner ranges are relative to their parent
+    /// and are unchanged, so the whole chain moves along the root sequence.
+    ///
+    /// Returns an error for whole-sequence identifiers, or if a coordinate
+    /// would fall below 1 or overflow.
+    pub fn shift(&self, delta: i64) -> Result<Self, SmittenError> {
+        let mut shifted_id = self.clone();
+        let range = shifted_id
+            .ranges
+            .first_mut()
+            .ok_or_else(|| SmittenError::WholeSequence(self.to_string()))?;
+
+        let shift = |pos: usize| {
+            isize::try_from(delta)
+                .ok()
+                .and_then(|delta| pos.checked_add_signed(delta))
+                .filter(|&pos| pos >= 1)
+                .ok_or_else(|| {
+                    SmittenError::CoordinateOverflow(format!(
+                        "shifting range {}-{} by {} moves it outside of 1-{}.",
+                        range.start, range.end, delta, usize::MAX
+                    ))
+                })
+        };
+        let (start, end) = (shift(range.start)?, shift(range.end)?);
+        range.start = start;
+        range.end = end;
+        Ok(shifted_id)
+    }
+
+    /// Normalizes the identifier and clamps the end of the resulting range
+    /// to `seq_len`.  Returns an error if the range starts beyond `seq_len`.
+    /// Whole-sequence identifiers are returned unchanged.
+    pub fn clamp_to_length(&self, seq_len: usize) -> Result<Self, SmittenError> {
+        let mut clamped_id = self.normalize()?;
+        if let Some(range) = clamped_id.ranges.first_mut() {
+            if range.start > seq_len {
+                return Err(SmittenError::SubrangeOutOfBounds {
+                    start: range.start,
+                    end: range.end,
+                    parent_len: seq_len,
+                });
+            }
+            range.end = range.end.min(seq_len);
+        }
+        Ok(clamped_id)
+    }
+
+    /// Returns `true` if the normalized range lies within `1..=len`, where
+    /// `len` is the length of `sequence_id` in `lengths`.  The assembly is
+    /// not part of the lookup.  A whole-sequence identifier always fits.
+    /// Requires the `std` feature.
+    ///
+    /// Returns an error if the sequence is not in `lengths` or the
+    /// identifier cannot be normalized.
+    #[cfg(feature = "std")]
+    pub fn within_lengths(&self, lengths: &HashMap<String, usize>) -> Result<bool, SmittenError> {
+        let seq_len = lengths
+            .get(&self.sequence_id)
+            .ok_or_else(|| SmittenError::UnknownSequence(self.sequence_id.clone()))?;
+        match self.absolute_range()? {
+            Some((start, end, _)) => Ok(start >= 1 && end <= *seq_len),
+            None => Ok(true),
+        }
+    }
+
+    /// Sets the assembly identifier, replacing any existing one.
+    pub fn with_assembly(mut self, assembly: impl Into<String>) -> Self {
+        self.assembly_id = Some(assembly.into());
+        self
+    }
+
+    /// Removes the assembly identifier, if any.
+    pub fn without_assembly(mut self) -> Self {
+        self.assembly_id = None;
+        self
+    }
+
+    /// Attaches an annotation, replacing any existing one.
+    pub fn with_annotation(mut self, annotation: &str) -> Self {
+        self.annotation = Some(annotation.to_string());
+        self
+    }
+
+    /// Returns the annotation attached with `with_annotation`, if any.
+    pub fn annotation(&self) -> Option<&str> {
+        self.annotation.as_deref()
+    }
+
+    /// Normalizes the identifier and tiles its range with windows of `size`
+    /// bases, starting every `step` bases.  Each window is returned as a
+    /// child range of the normalized identifier, e.g. `chr1:101-200_+:1-10_+`.
+    /// The final window is clipped to the end of the range, and tiling stops
+    /// once a window reaches it.
+    ///
+    /// Returns an error if `size` or `step` is zero, or for a whole-sequence
+    /// identifier.
+    pub fn windows(&self, size: usize, step: usize) -> Result<Vec<Identifier>, SmittenError> {
+        if size == 0 || step == 0 {
+            return Err(SmittenError::InvalidWindow { size, step });
+        }
+        let parent_id = self.normalize()?;
+        let parent_len = parent_id
+            .length()?
+            .ok_or_else(|| SmittenError::WholeSequence(self.to_string()))?;
+
+        let mut windows = Vec::new();
+        let mut start: usize = 1;
+        loop {
+            let end = start.saturating_add(size - 1).min(parent_len);
+            windows.push(parent_id.project_child(start, end, Strand::Forward)?);
+            if end == parent_len {
+                break;
+            }
+            start = match start.checked_add(step) {
+                Some(next) if next <= parent_len => next,
+                _ => break,
+            };
+        }
+        Ok(windows)
+    }
+
+    /// Returns the sequence identifier, ignoring any assembly.
+    pub fn sequence_key(&self) -> &str {
+        &self.sequence_id
+    }
+
+    /// Returns the root sequence as `assembly:sequence`, or just `sequence`
+    /// if there is no assembly.
+    pub fn full_root_key(&self) -> String {
+        match &self.assembly_id {
+            Some(assembly) => format!("{}:{}", assembly, self.sequence_id),
+            None => self.sequence_id.clone(),
+        }
+    }
+
+    /// Walks the ranges from outermost to innermost and checks that every
+    /// range is one-based and increasing, and that each child range ends within the
+    /// length of its parent.  The error for an overrunning child records its
+    /// level, with 0 being the outermost range.
+    ///
+    /// `from_v2` only checks each range on its own, so this can be used to
+    /// reject inconsistent chains up front rather than when normalizing.
+    pub fn validate_chain(&self) -> Result<(), SmittenError> {
+        let mut parent_len = None;
+        for (level, range) in self.ranges.iter().enumerate() {
+            if range.start == 0 || range.end == 0 {
+                return Err(SmittenError::ZeroCoordinate { start: range.start, end: range.end });
+            }
+            let len = Identifier::range_length(range)?;
+            if let Some(parent_len) = parent_len {
+                if range.end > parent_len {
+                    return Err(SmittenError::ChainOutOfBounds {
+                        level,
+                        start: range.start,
+                        end: range.end,
+                        parent_len,
+                    });
+                }
+            }
+            parent_len = Some(len);
+        }
+        Ok(())
+    }
+
+    /// Remaps the innermost range `[start, end]` to the coordinates counted
+    /// from the other end of a sequence of `seq_len` bases,
+    /// `[seq_len - end + 1, seq_len - start + 1]`, and flips its orientation.
+    /// Unlike `reverse_complement`, the coordinates themselves change.
+    ///
+    /// Returns an error for whole-sequence identifiers or if the range ends
+    /// beyond `seq_len`.
+    pub fn mirror(&self, seq_len: usize) -> Result<Self, SmittenError> {
+        let mut mirrored_id = self.clone();
+        let range = mirrored_id
+            .ranges
+            .last_mut()
+            .ok_or_else(|| SmittenError::WholeSequence(self.to_string()))?;
+        Identifier::check_range_bounds(range.start, range.end, Some(seq_len))?;
+
+        let (start, end) = (seq_len - range.end + 1, seq_len - range.start + 1);
+        range.start = start;
+        range.end = end;
+        range.orientation = range.orientation.complement();
+        Ok(mirrored_id)
+    }
+
+    /// Returns the number of chained ranges, 0 for a whole-sequence identifier.
+    pub fn depth(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns the absolute span on the root sequence as a zero-based
+    /// half-open `start - 1..end` interval, or `None` for whole-sequence
+    /// identifiers.  Orientation is dropped, and a zero start is an error.
+    pub fn as_interval(&self) -> Result<Option<core::ops::Range<usize>>, SmittenError> {
+        self.absolute_range()?
+            .map(|(start, end, _)| Ok(Identifier::zero_based_start(start, end)?..end))
+            .transpose()
+    }
+
+    /// Returns a multi-line breakdown of the identifier for diagnostics: the
+    /// assembly (if any), the sequence, one `level N: start-end (orient)` line
+    /// per range from the outermost, and the normalized identifier or the
+    /// error preventing normalization.
+    pub fn explain(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(assembly) = &self.assembly_id {
+            lines.push(format!("assembly: {}", assembly));
+        }
+        lines.push(format!("sequence: {}", self.sequence_id));
+        for (level, range) in self.ranges.iter().enumerate() {
+            lines.push(format!("level {}: {}-{} ({})", level + 1, range.start, range.end, range.orientation));
+        }
+        match self.normalize() {
+            Ok(normalized_id) => lines.push(format!("normalized: {}", normalized_id)),
+            Err(err) => lines.push(format!("normalized: error: {}", err)),
+        }
+        lines.join("\n")
+    }
+
+    /// Returns the identifier truncated at each nesting level, from the root
+    /// sequence to the full identifier, e.g. `hg38:chr1`,
+    /// `hg38:chr1:100-200_+`, `hg38:chr1:100-200_+:10-50_-`.
+    pub fn ancestors(&self) -> Vec<Identifier> {
+        (0..=self.ranges.len())
+            .map(|depth| {
+                let mut ancestor = self.root();
+                ancestor.ranges = self.ranges[..depth].to_vec();
+                ancestor
+            })
+            .collect()
+    }
+
+    /// Returns a copy keeping only the outermost `max_depth` ranges, e.g.
+    /// `chr1:100-200_+:10-50_-:1-5_+` truncated to 1 is `chr1:100-200_+`.
+    /// The discarded inner ranges are lost, so the result usually spans
+    /// more of the sequence than the original.
+    pub fn truncate_depth(&self, max_depth: usize) -> Identifier {
+        let mut truncated_id = self.clone();
+        truncated_id.ranges.truncate(max_depth);
+        truncated_id
+    }
+
+    /// Replaces the range at `index` (0 is the outermost), checking that it
+    /// is increasing, one-based, lies within its parent range, and still
+    /// contains its child range.  The identifier is unchanged on error.
+    pub fn set_range(&mut self, index: usize, start: usize, end: usize, orientation: Strand) -> Result<(), SmittenError> {
+        if index >= self.ranges.len() {
+            return Err(SmittenError::RangeIndexOutOfBounds { index, depth: self.ranges.len() });
+        }
+        if start > end {
+            return Err(SmittenError::DecreasingRange { start, end });
+        }
+        if start == 0 {
+            return Err(SmittenError::ZeroCoordinate { start, end });
+        }
+        let range = Range { start, end, orientation };
+        let parent_len = index
+            .checked_sub(1)
+            .map(|parent| Identifier::range_length(&self.ranges[parent]))
+            .transpose()?;
+        Identifier::check_range_bounds(start, end, parent_len)?;
+        if let Some(child) = self.ranges.get(index + 1) {
+            Identifier::check_range_bounds(child.start, child.end, Some(Identifier::range_length(&range)?))?;
+        }
+
+        self.ranges[index] = range;
+        Ok(())
+    }
+
+    /// Normalizes the identifier and extends its range by `upstream` bases
+    /// before it and `downstream` bases after it, reading in the direction
+    /// of its strand:
+    ///
+    /// * `+`: `upstream` lowers the start and `downstream` raises the end.
+    /// * `-`: `upstream` raises the end and `downstream` lowers the start,
+    ///   since the 5' end of a reverse strand range is its highest coordinate.
+    ///
+    /// The start is clamped at 1.  The end is not clamped, as the sequence
+    /// length is unknown; see `clamp_to_length`.  Whole-sequence identifiers
+    /// return an error.
+    pub fn pad(&self, upstream: usize, downstream: usize) -> Result<Self, SmittenError> {
+        let mut padded_id = self.normalize()?;
+        let range = padded_id
+            .ranges
+            .first_mut()
+            .ok_or_else(|| SmittenError::WholeSequence(self.to_string()))?;
+
+        let (before, after) = match range.orientation {
+            Strand::Forward => (upstream, downstream),
+            Strand::Reverse => (downstream, upstream),
+        };
+        range.start = range.start.saturating_sub(before).max(1);
+        range.end = range.end.checked_add(after).ok_or_else(|| {
+            SmittenError::CoordinateOverflow(format!("padding end {} by {}", range.end, after))
+        })?;
+        Ok(padded_id)
+    }
+
+    /// Returns `true` if any child range is `1-N` where `N` is the length of
+    /// its parent range, so that it covers exactly the same absolute
+    /// coordinates as the parent and the nesting level is redundant.  The
+    /// child's orientation is not considered, since a full-cover `-` child
+    /// only flips the strand.  Whole-sequence and single-range identifiers
+    /// are never degenerate.
+    pub fn is_degenerate(&self) -> bool {
+        self.ranges
+            .windows(2)
+            .any(|pair| pair[1].start == 1 && Identifier::range_length(&pair[0]).ok() == Some(pair[1].end))
+    }
+}
+
+/// Merges overlapping or adjacent identifiers into the minimal set of
+/// non-overlapping single-range identifiers.  Identifiers are grouped by
+/// root sequence and, by default, strand; see `merge_ranges_with` to merge
+/// across strands.  The result is sorted in genomic order.
+///
+/// Whole-sequence identifiers have no span to merge and return an error.
+pub fn merge_ranges(ids: &[Identifier]) -> Result<Vec<Identifier>, SmittenError> {
+    merge_ranges_with(ids, false)
+}
+
+/// Merges identifiers like `merge_ranges`.  When `merge_strands` is true,
+/// spans on opposite strands are merged together and the merged identifier
+/// is oriented `+` unless all of its inputs are `-`.
+pub fn merge_ranges_with(ids: &[Identifier], merge_strands: bool) -> Result<Vec<Identifier>, SmittenError> {
+    let mut groups: BTreeMap<(Identifier, Option<Strand>), Vec<Span>> = BTreeMap::new();
+    for id in ids {
+        let span = id
+            .absolute_range()?
+            .ok_or_else(|| SmittenError::WholeSequence(id.to_string()))?;
+        let strand = if merge_strands { None } else { Some(span.2) };
+        groups
+            .entry((id.root(), strand))
+            .or_default()
+            .push(span);
+    }
+
+    let mut merged_ids = Vec::new();
+    for ((root_id, _), mut spans) in groups {
+        spans.sort();
+        let mut merged: Vec<Span> = Vec::new();
+        for (start, end, orientation) in spans {
+            match merged.last_mut() {
+                Some(last) if start.saturating_sub(1) <= last.1 => {
+                    last.1 = last.1.max(end);
+                    if orientation == Strand::Forward {
+                        last.2 = Strand::Forward;
+                    }
+                }
+                _ => merged.push((start, end, orientation)),
+            }
+        }
+
+        for (start, end, orientation) in merged {
+            merged_ids.push(root_id.root_with_range(start, end, orientation));
+        }
+    }
+
+    merged_ids.sort();
+    Ok(merged_ids)
+}
+
+/// A borrowed view of a V2 identifier, produced by
+/// `Identifier::parse_id_ref` without allocating.  The names point into the
+/// parsed string, and the ranges are decoded from it on demand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdentifierRef<'a> {
+    pub assembly_id: Option<&'a str>,
+    pub sequence_id: &'a str,
+    // The validated `:start-end_orient` ranges, outermost first
+    ranges_suffix: &'a str,
+}
+
+impl<'a> IdentifierRef<'a> {
+    /// Iterates over the ranges from the outermost to the innermost.
+    pub fn ranges(&self) -> impl Iterator<Item = Range> + 'a {
+        self.ranges_suffix
+            .split(':')
+            .skip(1)
+            .filter_map(|token| Identifier::parse_range_token(token).ok())
+    }
+
+    /// Returns the number of chained ranges.
+    pub fn depth(&self) -> usize {
+        self.ranges_suffix.matches(':').count()
+    }
+
+    /// Copies the view into an owned `Identifier`, equal to the result of
+    /// `from_v2` on the same input.
+    pub fn to_owned(&self) -> Identifier {
+        Identifier {
+            assembly_id: self.assembly_id.map(str::to_string),
+            sequence_id: self.sequence_id.to_string(),
+            ranges: self.ranges().collect(),
+            inferred_version: IDVersion::V2,
+            whole_sequence_strand: None,
+            annotation: None,
+        }
+    }
+}
+
+/// Incrementally constructs a chained V2 `Identifier`, validating each range
+/// against its parent as it is pushed.
+///
+/// ```rust
+/// use smitten::{IdentifierBuilder, Strand};
+///
+/// let id = IdentifierBuilder::new()
+///     .assembly("hg38")
+///     .sequence("chr1")
+///     .push_range(100, 200, Strand::Forward).unwrap()
+///     .push_range(10, 50, Strand::Reverse).unwrap()
+///     .build()
+///     .unwrap();
+/// assert_eq!(id.to_string(), "hg38:chr1:100-200_+:10-50_-");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IdentifierBuilder {
+    assembly_id: Option<String>,
+    sequence_id: String,
+    ranges: Vec<Range>,
+}
+
+impl IdentifierBuilder {
+    pub fn new() -> Self {
+        IdentifierBuilder::default()
+    }
+
+    pub fn assembly(mut self, assembly: &str) -> Self {
+        self.assembly_id = Some(assembly.to_string());
+        self
+    }
+
+    pub fn sequence(mut self, sequence: &str) -> Self {
+        self.sequence_id = sequence.to_string();
+        self
+    }
+
+    /// Appends a new innermost range, returning an error if it is decreasing,
+    /// not one-based, or outside the length of the previous range.
+    pub fn push_range(mut self, start: usize, end: usize, orientation: Strand) -> Result<Self, SmittenError> {
+        let range = Range { start, end, orientation };
+        let parent_len = self.ranges.last().map(|parent| parent.end - parent.start + 1);
+        Identifier::check_ranges(core::slice::from_ref(&range))?;
+        Identifier::check_range_bounds(start, end, parent_len)?;
+        self.ranges.push(range);
+        Ok(self)
+    }
+
+    pub fn build(self) -> Result<Identifier, SmittenError> {
+        Identifier::new(self.assembly_id, self.sequence_id, self.ranges)
+    }
+}
+
+/// Buckets identifiers by their `(assembly_id, sequence_id)` root, preserving
+/// the input order within each bucket.  Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn group_by_root<I: IntoIterator<Item = Identifier>>(ids: I) -> HashMap<(Option<String>, String), Vec<Identifier>> {
+    let mut groups: HashMap<(Option<String>, String), Vec<Identifier>> = HashMap::new();
+    for id in ids {
+        groups
+            .entry((id.assembly_id.clone(), id.sequence_id.clone()))
+            .or_default()
+            .push(id);
+    }
+    groups
+}
+
+/// Guesses the coordinate system of a batch of identifiers of unknown format
+/// from their raw ranges.  A zero coordinate only occurs in zero-based
+/// half-open data, while a single-base range `N-N` would be empty there and
+/// so implies one-based fully-closed coordinates.
+///
+/// Only the innermost `MAX_RANGE_DEPTH` ranges of each identifier are
+/// considered.  Returns `None` if neither sign is present, or if both are.
+pub fn guess_coord_system(ids: &[&str]) -> Option<CoordSystem> {
+    let mut zero_coordinate = false;
+    let mut single_base = false;
+
+    for id in ids {
+        let mut sequence_id = *id;
+        let mut depth = 0;
+        while let Some(captures) = CONVERT_ID_RE.captures(sequence_id) {
+            if depth >= MAX_RANGE_DEPTH || Identifier::infer_range_format(&captures) == IDVersion::Undefined {
+                break;
+            }
+            depth += 1;
+            // Coordinates too large for a usize carry no evidence either way
+            if let (Ok(start), Ok(end)) = (captures[4].parse::<usize>(), captures[6].parse::<usize>()) {
+                zero_coordinate |= start == 0 || end == 0;
+                single_base |= start == end && start != 0;
+            }
+            sequence_id = captures.get(1).map_or("", |m| m.as_str());
+        }
+    }
+
+    match (zero_coordinate, single_base) {
+        (true, false) => Some(CoordSystem::ZeroBasedHalfOpen),
+        (false, true) => Some(CoordSystem::OneBasedFullyClosed),
+        _ => None,
+    }
+}
+
+/// Counts identifiers by the strand of their normalized range, returning
+/// `(forward_count, reverse_count, whole_sequence_count)`.  Whole-sequence
+/// identifiers are counted in the third bucket regardless of any
+/// `whole_sequence_strand`.
+///
+/// Returns the first normalization error encountered.
+pub fn strand_histogram<'a, I: IntoIterator<Item = &'a Identifier>>(ids: I) -> Result<(usize, usize, usize), SmittenError> {
+    let mut counts = (0, 0, 0);
+    for id in ids {
+        match id.absolute_range()? {
+            Some((_, _, Strand::Forward)) => counts.0 += 1,
+            Some((_, _, Strand::Reverse)) => counts.1 += 1,
+            None => counts.2 += 1,
+        }
+    }
+    Ok(counts)
+}
+
+/// Returns the single-range identifier spanning all of `ids`, from the
+/// smallest normalized start to the largest normalized end, ignoring any
+/// gaps.  The result is always oriented `+`, since the inputs may be on
+/// either strand.
+///
+/// All identifiers must share the same root sequence.  An empty slice or a
+/// whole-sequence identifier, which has no span, returns an error.
+pub fn bounding(ids: &[Identifier]) -> Result<Identifier, SmittenError> {
+    let first = ids.first().ok_or(SmittenError::NoIdentifiers)?;
+    let mut bounds: Option<(usize, usize)> = None;
+    for id in ids {
+        let (start, end, _) = match first.root_spans(id)? {
+            (_, Some(span)) => span,
+            (_, None) => return Err(SmittenError::WholeSequence(id.to_string())),
+        };
+        bounds = Some(bounds.map_or((start, end), |(low, high)| (low.min(start), high.max(end))));
+    }
+
+    let (start, end) = bounds.ok_or(SmittenError::NoIdentifiers)?;
+    Ok(first.root_with_range(start, end, Strand::Forward))
+}
+
+/// Normalizes each identifier and drops those whose normalized form was
+/// already seen, so differently written chains or versions of the same span
+/// collapse to one, e.g. `chr1:200-100` (V1) and `chr1:100-200_-`.  The
+/// normalized identifiers are returned in order of first occurrence, and
+/// spans on opposite strands are kept apart.
+///
+/// Returns the first normalization error, if any.
+pub fn dedup_by_span(ids: Vec<Identifier>) -> Result<Vec<Identifier>, SmittenError> {
+    let mut seen = BTreeSet::new();
+    let mut unique_ids = Vec::new();
+    for id in ids {
+        let normalized_id = id.normalize()?;
+        if seen.insert(normalized_id.clone()) {
+            unique_ids.push(normalized_id);
+        }
+    }
+    Ok(unique_ids)
+}
+
+
+impl core::fmt::Display for IDVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let version_str = match self {
+            IDVersion::Undefined => "Undefined",
+            IDVersion::WholeSequence => "WholeSequence",
+            IDVersion::Mixed => "Mixed",
+            IDVersion::V0 => "V0",
+            IDVersion::V1 => "V1",
+            IDVersion::V2 => "V2",
+        };
+    write!(f, "{}", version_str)
+    }
+}
+
+/// Renders the identifier in V2 format.  The alternate form (`{:#}`) omits
+/// the assembly prefix.
+impl core::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let mut v2_id = String::new();
+
+        if let Some(assembly) = &self.assembly_id {
+            if !f.alternate() {
+                v2_id.push_str(&format!("{}:", assembly));
+            }
+        }
+
+        v2_id.push_str(&self.sequence_id);
+
+        if !self.ranges.is_empty() {
+            for range in &self.ranges {
+                v2_id.push_str(&format!(":{}-{}_{}", range.start, range.end, range.orientation));
+            }
+        } else if let Some(strand) = self.rangeless_strand() {
+            v2_id.push_str(&format!("_{}", strand));
+        }
+
+        write!(f, "{}", v2_id)
+    }
+}
+
+/// Parses a V2 identifier, equivalent to calling `Identifier::from_v2`.
+///
+/// Callers that need V0/V1 inference should continue to use
+/// `Identifier::from_unknown_format`.
+impl core::str::FromStr for Identifier {
+    type Err = SmittenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Identifier::from_v2(s)
+    }
+}
+
+/// Parses a V2 identifier, equivalent to calling `Identifier::from_v2`.
+impl TryFrom<&str> for Identifier {
+    type Error = SmittenError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Identifier::from_v2(s)
+    }
+}
+
+/// Parses a V2 identifier, equivalent to calling `Identifier::from_v2`.
+impl TryFrom<String> for Identifier {
+    type Error = SmittenError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Identifier::from_v2(&s)
+    }
+}
+
+impl core::fmt::Display for Strand {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+/// Parses a V2 orientation token, `+` or `-`.
+impl core::str::FromStr for Strand {
+    type Err = SmittenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "+" => Ok(Strand::Forward),
+            "-" => Ok(Strand::Reverse),
+            _ => Err(SmittenError::InvalidOrientation(s.chars().next().unwrap_or(' '))),
+        }
+    }
+}
+
+impl From<Identifier> for String {
+    fn from(id: Identifier) -> Self {
+        id.to_string()
+    }
+}
+
+impl Identifier {
+    /// Converts a Smitten format identifier in V0, V1, or V2 to V2 format.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Sequence identifier in V0, V1, or V2 format
+    /// * `zbho` - Boolean flag; if true, treats coordinate ranges as zero-based half-open (ZBHO)
+    /// * `allow_mixed` - Boolean flag; if true, allows mixed V0/V1/V2 identifiers
+    ///
+    /// # Returns
+    ///
+    /// Returns a tuple `(String, IDVersion)` with the V2 equivalent identifier and inferred version.
+    ///
+    fn convert_id(id: &str, zbho: bool, allow_mixed: bool) -> Result<(String, IDVersion), SmittenError> {
+        Identifier::check_whitespace(id)?;
+
+        let re = &*CONVERT_ID_RE;
+        let mut inferred_fmt = None;
+        let mut sequence_id = id.to_string();
+        let mut ranges = Vec::new();
+
+        // Iterate over matches and parse ranges
+        while let Some(captures) = re.captures(&sequence_id) {
+            let start = Identifier::parse_coordinate(&captures[4])?;
+            let end = Identifier::parse_coordinate(&captures[6])?;
+            let orientation = captures.get(9).map_or(Strand::Forward, |m| {
+                if m.as_str() == "+" { Strand::Forward } else { Strand::Reverse }
+            });
+
+            let start = if zbho {
+                start.checked_add(1).ok_or_else(|| {
+                    SmittenError::CoordinateOverflow(format!("zero-based start {} cannot be converted", start))
+                })?
+            } else {
+                start
+            };
+
+            // Infer format based on separators 
+            let range_fmt = Identifier::infer_range_format(&captures);
+
+            if range_fmt == IDVersion::Undefined {
+                break;
+            }
+
+            if let Some(ref inferred) = inferred_fmt {
+                if *inferred != range_fmt {
+                    if allow_mixed {
+                        inferred_fmt = Some(IDVersion::Mixed); 
+                    }else {
+                        break;
+                    }
+                }
+            } else {
+                inferred_fmt = Some(range_fmt.clone());
+            }
+
+            let (ordered_start, ordered_end, final_orientation) = match range_fmt {
+                IDVersion::V0 | IDVersion::V2 if start > end => {
+                    return Err(SmittenError::DecreasingRange { start, end });
+                }
+                IDVersion::V1 if start > end => (end, start, Strand::Reverse),
+                IDVersion::V1 => (start, end, Strand::Forward),
+                _ => (start, end, orientation),
+            };
+
+            if ranges.len() >= MAX_RANGE_DEPTH {
+                return Err(SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH));
+            }
+
+            ranges.push(Range {
+                start: ordered_start,
+                end: ordered_end,
+                orientation: final_orientation,
+            });
+
+            sequence_id = captures[1].to_string();
+        }
+
+        if ranges.is_empty() {
+            inferred_fmt = Some(Identifier::rangeless_version(id));
+        }
+
+        let ids: Vec<&str> = sequence_id.split(':').collect();
+        let (assembly_id, sequence_id) = match (sequence_id.matches(':').count(), ids.len()) {
+            (1, 2) if !ids[0].is_empty() && !ids[1].is_empty() => (Some(ids[0]), ids[1]),
+            (0, 1) if !sequence_id.is_empty() => (None, sequence_id.as_str()),
+            _ => {
+                return Err(Identifier::assembly_structure_error(id, &sequence_id));
+            }
+        };
+
+        let mut v2_id = if let Some(assembly) = assembly_id {
+            format!("{}:{}", assembly, sequence_id)
+        } else {
+            sequence_id.to_string()
+        };
+
+        let mut current_parent_length = None;
+        for range in ranges.iter().rev() {
+            Identifier::check_range_bounds(range.start, range.end, current_parent_length)?;
+            current_parent_length = Some(range.end - range.start + 1);
+            v2_id.push_str(&format!(":{}-{}_{}", range.start, range.end, range.orientation));
+        }
+
+        Ok((v2_id, inferred_fmt.unwrap_or(IDVersion::Undefined)))
+    }
+
+    /// Parses a Smitten format sequence identifier, returning the individual components.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Sequence identifier in V0, V1, or V2 format
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Identifier` struct containing `assembly_id`, `sequence_id`, `ranges`, and inferred `version`.
+    ///
+    fn parse_id(id: &str) -> Result<Self, SmittenError> {
+        Identifier::parse_id_with_depth(id, MAX_RANGE_DEPTH)
+    }
+
+    /// Parses a V2 identifier like `parse_id`, allowing at most `max_depth`
+    /// chained ranges instead of the default `MAX_RANGE_DEPTH`.
+    pub fn parse_id_with_depth(id: &str, max_depth: usize) -> Result<Self, SmittenError> {
+        Identifier::parse_id_with(id, &PARSE_ID_RE, max_depth)
+    }
+
+    /// Parses a V2 identifier whose ranges are matched by `re`, which must
+    /// have the same capture groups as `PARSE_ID_RE`.
+    fn parse_id_with(id: &str, re: &Regex, max_depth: usize) -> Result<Self, SmittenError> {
+        Identifier::check_whitespace(id)?;
+
+        let mut assembly_id = None;
+        let (id_str, ranges) = Identifier::parse_ranges(id, re, max_depth)?;
+
+        let ids: Vec<&str> = id_str.split(':').collect();
+        let sequence_id;
+        if ids.len() == 2 {
+            if ids[0].is_empty() {
+                return Err(SmittenError::EmptyAssemblyId(id.to_string()));
+            }
+            assembly_id = Some(ids[0].to_string());
+            sequence_id = ids[1].to_string();
+        } else if ids.len() == 1 {
+            sequence_id = ids[0].to_string();
+        } else {
+            return Err(Identifier::assembly_structure_error(id, &id_str));
+        }
+
+        if sequence_id.is_empty() {
+            return Err(SmittenError::EmptySequenceId(id.to_string()));
+        }
+
+        Ok(Identifier {
+            assembly_id,
+            sequence_id,
+            ranges,
+            inferred_version: IDVersion::V2,
+            whole_sequence_strand: None,
+            annotation: None,
+        })
+    }
+
+    /// Splits a trailing `:start-end[_orient]` range off `id`, returning the
+    /// prefix before the ':' and the range token after it.  This matches the
+    /// same suffix as `PARSE_ID_RE`, whose coordinates are also ASCII digits.
+    fn split_last_range(id: &str) -> Option<(&str, &str)> {
+        let bytes = id.as_bytes();
+        let digits_before = |end: usize| end - bytes[..end].iter().rev().take_while(|b| b.is_ascii_digit()).count();
+
+        let mut end = bytes.len();
+        if id.ends_with("_+") || id.ends_with("_-") {
+            end -= 2;
+        }
+        let end_digits = digits_before(end);
+        if end_digits == end || end_digits == 0 || bytes[end_digits - 1] != b'-' {
+            return None;
+        }
+        let start_digits = digits_before(end_digits - 1);
+        if start_digits == end_digits - 1 || start_digits == 0 || bytes[start_digits - 1] != b':' {
+            return None;
+        }
+        Some((&id[..start_digits - 1], &id[start_digits..]))
+    }
+
+    /// Parses a `start-end[_orient]` token found by `split_last_range`.
+    fn parse_range_token(token: &str) -> Result<Range, SmittenError> {
+        let (coords, orientation) = match token.split_once('_') {
+            Some((coords, orient)) => (coords, orient.parse::<Strand>()?),
+            None => (token, Strand::Forward),
+        };
+        let (start, end) = coords.split_once('-').ok_or_else(|| SmittenError::InvalidRangeSuffix(token.to_string()))?;
+        Ok(Range {
+            start: Identifier::parse_coordinate(start)?,
+            end: Identifier::parse_coordinate(end)?,
+            orientation,
+        })
+    }
+
+    /// Removes V2 ranges from the end of `id`, returning the remaining
+    /// prefix and the ranges in outer-to-inner order.
+    fn parse_ranges(id: &str, re: &Regex, max_depth: usize) -> Result<(String, Vec<Range>), SmittenError> {
+        let mut ranges = Vec::new();
+        let mut id_str = id.to_string();
+
+        // Remove ranges from the end of the ID string
+        while let Some(captures) = re.captures(&id_str) {
+            let start = Identifier::parse_coordinate(&captures[4])?;
+            let end = Identifier::parse_coordinate(&captures[6])?;
+            // A missing orientation defaults to forward strand, as in convert_id
+            let orientation = captures
+                .get(9)
+                .map_or(Ok(Strand::Forward), |m| m.as_str().parse::<Strand>())?;
+
+            if start > end {
+                return Err(SmittenError::DecreasingRange { start, end });
+            }
+
+            if ranges.len() >= max_depth {
+                return Err(SmittenError::RangeDepthExceeded(max_depth));
+            }
+
+            ranges.push(Range { start, end, orientation });
+            id_str = captures[1].to_string();
+        }
+
+        ranges.reverse();
+        Ok((id_str, ranges))
+    }
+
+    /// Infers the format version of a single range suffix captured by the
+    /// `convert_id` pattern from its separators.
+    fn infer_range_format(captures: &regex::Captures) -> IDVersion {
+        if captures.get(3).map(|s| s.as_str()) == Some(":")
+            && captures.get(5).map(|s| s.as_str()) == Some("-")
+            && ( captures.get(7).map(|s| s.as_str()) == Some("_+") ||
+                 captures.get(7).map(|s| s.as_str()) == Some("_-") )
+        {
+            IDVersion::V2
+        } else if captures.get(3).map(|s| s.as_str()) == Some(":")
+               && captures.get(5).map(|s| s.as_str()) == Some("-")
+               && captures.get(7).is_none()
+        {
+            IDVersion::V1
+
+        // The first case is a special case of v0 (e.g "chr1:100-200_R") that was in-use by
+        // Arian for awhile...sigh
+        } else if (captures.get(3).map(|s| s.as_str()) == Some(":")
+                   && captures.get(5).map(|s| s.as_str()) == Some("-")
+                   && (captures.get(7).is_none() || captures.get(7).map(|s| s.as_str()) == Some("_R") ))
+               || (captures.get(3).map(|s| s.as_str()) == Some("_")
+                   && captures.get(5).map(|s| s.as_str()) == Some("_")
+                   && (captures.get(7).is_none() || captures.get(7).map(|s| s.as_str()) == Some("_R") ))
+        {
+            IDVersion::V0
+
+        } else {
+            IDVersion::Undefined
+        }
+    }
+
+    /// Checks that a range is valid in a one-based fully-closed coordinate
+    /// system and, if a parent range length is given, that it lies within it.
+    fn check_range_bounds(start: usize, end: usize, parent_len: Option<usize>) -> Result<(), SmittenError> {
+        if start == 0 || end == 0 {
+            return Err(SmittenError::ZeroCoordinate { start, end });
+        }
+        if let Some(parent_len) = parent_len {
+            if start > parent_len || end > parent_len {
+                return Err(SmittenError::SubrangeOutOfBounds { start, end, parent_len });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every range is increasing, one-based, and lies within the
+    /// length of its parent range.
+    fn check_ranges(ranges: &[Range]) -> Result<(), SmittenError> {
+        let mut parent_len = None;
+        for range in ranges {
+            if range.start > range.end {
+                return Err(SmittenError::DecreasingRange { start: range.start, end: range.end });
+            }
+            Identifier::check_range_bounds(range.start, range.end, parent_len)?;
+            parent_len = Some(range.end - range.start + 1);
+        }
+        Ok(())
+    }
+
+    /// Expresses a child span in the coordinates of its parent's frame,
+    /// returning the coordinates in increasing order.  The child must lie
+    /// within the parent range.
+    fn fold_span(parent: &Range, (start_idx, end_idx, curr_orient): Span) -> Result<Span, SmittenError> {
+        let overflow = || {
+            SmittenError::CoordinateOverflow(format!(
+                "range {}-{} cannot be placed within parent range {}-{}_{}.",
+                start_idx, end_idx, parent.start, parent.end, parent.orientation
+            ))
+        };
+
+        let parent_len = parent.end
+            .checked_sub(parent.start)
+            .and_then(|len| len.checked_add(1))
+            .ok_or_else(overflow)?;
+        Identifier::check_range_bounds(start_idx, end_idx, Some(parent_len))?;
+
+        let (start_idx, end_idx) = if parent.orientation == Strand::Reverse {
+            let flip = |idx: usize| parent.end.checked_sub(idx).and_then(|pos| pos.checked_add(1));
+            (flip(start_idx).ok_or_else(overflow)?, flip(end_idx).ok_or_else(overflow)?)
+        } else {
+            let shift = |idx: usize| parent.start.checked_add(idx).and_then(|pos| pos.checked_sub(1));
+            (shift(start_idx).ok_or_else(overflow)?, shift(end_idx).ok_or_else(overflow)?)
+        };
+
+        let curr_orient = parent.orientation.compose(curr_orient);
 
         if start_idx < end_idx {
-            ret_str.push_str(&format!("{}-{}_{}", start_idx, end_idx, curr_orient));
+            Ok((start_idx, end_idx, curr_orient))
+        } else {
+            Ok((end_idx, start_idx, curr_orient))
+        }
+    }
+
+    /// Returns the absolute ranges of two identifiers after checking that they
+    /// share the same root sequence.
+    fn root_spans(&self, other: &Identifier) -> Result<(Option<Span>, Option<Span>), SmittenError> {
+        if self.assembly_id != other.assembly_id || self.sequence_id != other.sequence_id {
+            return Err(SmittenError::RootMismatch {
+                left: self.to_string(),
+                right: other.to_string(),
+            });
+        }
+        Ok((self.absolute_range()?, other.absolute_range()?))
+    }
+
+    /// Returns the `whole_sequence_strand` if the identifier has no ranges,
+    /// the only case in which it is rendered.
+    fn rangeless_strand(&self) -> Option<Strand> {
+        self.whole_sequence_strand.filter(|_| self.ranges.is_empty())
+    }
+
+    /// Returns an identifier on the same root sequence with a single range.
+    fn root_with_range(&self, start: usize, end: usize, orientation: Strand) -> Identifier {
+        let mut root_id = self.root();
+        root_id.ranges.push(Range { start, end, orientation });
+        root_id
+    }
+
+    /// Rejects identifiers containing whitespace, reporting the byte offset
+    /// of the first whitespace character.
+    fn check_whitespace(id: &str) -> Result<(), SmittenError> {
+        match id.find(char::is_whitespace) {
+            Some(position) => Err(SmittenError::WhitespaceInIdentifier { id: id.to_string(), position }),
+            None => Ok(()),
+        }
+    }
+
+    /// Builds the error for a malformed `assembly:sequence` prefix of `id`,
+    /// pointing at the second ':' or, if there is only one, at the ':'
+    /// next to the empty component.
+    fn assembly_structure_error(id: &str, prefix: &str) -> SmittenError {
+        let mut colons = prefix.match_indices(':').map(|(position, _)| position);
+        let first = colons.next();
+        let position = colons.next().or(first).unwrap_or(0);
+        SmittenError::InvalidAssemblyStructure { id: id.to_string(), position }
+    }
+
+    /// Returns `end - start + 1` for a single range, checking that the range
+    /// is increasing and that its length fits in a `usize`.
+    fn range_length(range: &Range) -> Result<usize, SmittenError> {
+        let span = range.end.checked_sub(range.start).ok_or(SmittenError::DecreasingRange {
+            start: range.start,
+            end: range.end,
+        })?;
+        span.checked_add(1).ok_or_else(|| {
+            SmittenError::CoordinateOverflow(format!("range {}-{} has a length beyond usize", range.start, range.end))
+        })
+    }
+
+    /// Converts a one-based `start` to zero-based, rejecting a zero start.
+    fn zero_based_start(start: usize, end: usize) -> Result<usize, SmittenError> {
+        start.checked_sub(1).ok_or(SmittenError::ZeroCoordinate { start, end })
+    }
+
+    /// Parses a coordinate matched by one of the range regexes, which
+    /// guarantee ASCII digits but not that the value fits in a `usize`.
+    fn parse_coordinate(digits: &str) -> Result<usize, SmittenError> {
+        digits
+            .parse::<usize>()
+            .map_err(|_| SmittenError::CoordinateOverflow(format!("coordinate {} does not fit in a usize", digits)))
+    }
+
+    /// Describes the ambiguity resolutions `convert_id` made in arriving at
+    /// `converted_id` with the inferred `version`.
+    fn conversion_notes(converted_id: &Identifier, version: &IDVersion) -> Vec<String> {
+        let mut notes = Vec::new();
+        let root_key = converted_id.full_root_key();
+
+        if let Some(captures) = CONVERT_ID_RE.captures(&root_key) {
+            let suffix = &captures[2];
+            let note = match Identifier::infer_range_format(&captures) {
+                IDVersion::Undefined => format!(
+                    "Kept '{}' as part of '{}' since it is not a recognized range.",
+                    suffix, root_key
+                ),
+                range_fmt => format!(
+                    "Kept '{}' as part of '{}' since its {} format differs from the inferred {} format.",
+                    suffix, root_key, range_fmt, version
+                ),
+            };
+            notes.push(note);
+        }
+
+        if *version == IDVersion::V0 && converted_id.sequence_id.contains('_') {
+            notes.push(format!(
+                "Sequence '{}' contains '_', which is also the V0 range separator; ranges were read from the end of the identifier.",
+                converted_id.sequence_id
+            ));
+        }
+
+        notes
+    }
+
+    /// Converts `id` and checks that it was inferred as `expected` or as a
+    /// whole sequence.
+    fn from_version(id: &str, expected: IDVersion) -> Result<Self, SmittenError> {
+        let (v2_id, found) = Identifier::convert_id(id, false, false)?;
+        if found != expected && found != IDVersion::WholeSequence {
+            return Err(SmittenError::VersionMismatch { id: id.to_string(), expected, found });
+        }
+        Identifier::parse_id(&v2_id)
+    }
+
+    /// Returns the version of an identifier from which no ranges were parsed:
+    /// `Undefined` if it ends in unrecognized range-like text such as
+    /// `chr1:200_100`, and `WholeSequence` otherwise.
+    fn rangeless_version(id: &str) -> IDVersion {
+        if CONVERT_ID_RE.is_match(id) {
+            IDVersion::Undefined
         } else {
-            ret_str.push_str(&format!("{}-{}_{}", end_idx, start_idx, curr_orient));
+            IDVersion::WholeSequence
+        }
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_convert_id_test(
+        id: &str,
+        coord_type: &str,
+        exp_outcome: &str,
+        exp_version: Option<IDVersion>,
+        exp_v2_format: &str,
+    ) {
+        let zbho = coord_type == "zbho";
+        let result = Identifier::from_unknown_format(id, zbho, false);
+
+        match (result.clone(), exp_outcome, exp_version.clone()) {
+            // Expected pass case
+            (Ok((converted_id, version)), "pass", Some(expected_version)) => {
+                assert_eq!(version, expected_version, "IDVersion mismatch for ID: {}", id);
+                assert_eq!(converted_id.to_string(), exp_v2_format, "V2 format mismatch for ID: {}", id);
+            }
+            // Expected failure with None as version
+            (Err(err), "fail", None) => {
+                println!("Expected failure for ID: {}. Error message: {}", id, err);
+            }
+            // Expected failure with Some(Undefined) as version
+            (Err(err), "fail", Some(IDVersion::Undefined)) => {
+                println!("Expected specific failure for ID: {}. Error message: {}", id, err);
+            }
+            // Unexpected success when failure was expected
+            (Ok((converted_id, version)), "fail", _) => {
+                panic!(
+                    "Unexpected success for ID: {}. Expected outcome: fail, but got success with V2 ID: '{}' and version: {:?}",
+                    id, converted_id, version
+                );
+            }
+            // Unexpected failure when pass was expected
+            (Err(err), "pass", Some(expected_version)) => {
+                panic!(
+                    "Unexpected failure for ID: {}. Expected outcome: pass, version: {:?}. Error: {}",
+                    id, expected_version, err
+                );
+            }
+            // Catch-all for any unexpected outcome
+            _ => panic!(
+                "Unexpected outcome for ID: {}. Expected outcome: {}, version: {:?}, but got result: {:?}",
+                id, exp_outcome, exp_version, result
+            ),
+        }
+    }
+
+    fn convert_id_test_cases() -> Vec<(&'static str, &'static str, &'static str, Option<IDVersion>, &'static str)> {
+        vec![
+            // V0 Examples
+            ("chr1_100_200", "obfc", "pass", Some(IDVersion::V0), "chr1:100-200_+"),
+            ("seq_100_200_R", "obfc", "pass", Some(IDVersion::V0), "seq:100-200_-"),
+            ("(ACC)n#Simple_1_10", "obfc", "pass", Some(IDVersion::V0), "(ACC)n#Simple:1-10_+"),
+            ("chr_1_100_200_10_20_R", "obfc", "pass", Some(IDVersion::V0), "chr_1:100-200_+:10-20_-"),
+            ("AMM1015_1000_2000_R_1_10_R", "obfc", "pass", Some(IDVersion::V0), "AMM1015:1000-2000_-:1-10_-"),
+            ("hg38:chr1_100_200", "obfc", "pass", Some(IDVersion::V0), "hg38:chr1:100-200_+"),
+            ("hg38:chr1_100_200_R", "obfc", "pass", Some(IDVersion::V0), "hg38:chr1:100-200_-"),
+            ("1_2000_3000_400_500_60_70_8_9", "obfc", "pass", Some(IDVersion::V0), "1:2000-3000_+:400-500_+:60-70_+:8-9_+"),
+            ("chr;1_100_200", "obfc", "pass", Some(IDVersion::V0), "chr;1:100-200_+"),
+            // Indistinguishable from a badly formatted V0 (with sequence positions in the wrong
+            // order)
+            ("NT_004321_0", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("_100_200", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("AMM::1002:Seq1:100_200", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            (":_100_200", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("chr_1_100_200_150_200_R", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("HSPA2_0_0", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("chr2_200_100", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            // V1 Examples
+            ("chr1:1-200", "obfc", "pass", Some(IDVersion::V1), "chr1:1-200_+"),
+            ("chr1:200-1", "obfc", "pass", Some(IDVersion::V1), "chr1:1-200_-"),
+            ("GCA1:chr2:540133-28232", "obfc", "pass", Some(IDVersion::V1), "GCA1:chr2:28232-540133_-"),
+            ("chr1:200-1:10-5", "obfc", "pass", Some(IDVersion::V1), "chr1:1-200_-:5-10_-"),
+            ("(ACC)n#Simple:400-500:20-1", "obfc", "pass", Some(IDVersion::V1), "(ACC)n#Simple:400-500_+:1-20_-"),
+            ("seq1@*@)(_:1-200", "obfc", "pass", Some(IDVersion::V1), "seq1@*@)(_:1-200_+"),
+            ("1:2000-3000:400-500:60-70:8-9", "obfc", "pass", Some(IDVersion::V1), "1:2000-3000_+:400-500_+:60-70_+:8-9_+"),
+            ("PB2_4_8_8:2709-6535", "obfc", "pass", Some(IDVersion::V1), "PB2_4_8_8:2709-6535_+"),
+            ("chromosome___:0-10", "zbho", "pass", Some(IDVersion::V1), "chromosome___:1-10_+"),
+            ("chr_1:100-200:200-150", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("ASeq:0-0", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("chr1:200-1:10-5_+", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("chr1:200-1_+", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("hg38:chr1:200-100_-", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("AMM::1002:Seq1:100-200", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("::100-200", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            // V2 Examples
+            ("chr1:100-200_+", "obfc", "pass", Some(IDVersion::V2), "chr1:100-200_+"),
+            ("chr1_1_5_3:1-3_+", "obfc", "pass", Some(IDVersion::V2), "chr1_1_5_3:1-3_+"),
+            ("chr1:100-200_+", "obfc", "pass", Some(IDVersion::V2), "chr1:100-200_+"),
+            ("hg38:chr1:100-200_+", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:100-200_+"),
+            ("hg38:chr1:100-200_-", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:100-200_-"),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:100-200_+:10-50_-:1-5_+"),
+            ("hg38:chr1:200-200_-", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:200-200_-"),
+            ("hg38:chr1:33438223-33439283_+", "zbho", "pass", Some(IDVersion::V2), "hg38:chr1:33438224-33439283_+"),
+            ("JANCRE010000006.1:5563658-5564462_-:1-458_+", "obfc", "pass", Some(IDVersion::V2), "JANCRE010000006.1:5563658-5564462_-:1-458_+"),
+            ("JANCRE010000006.1_5563658_5564462_R:1-458_+", "obfc", "pass", Some(IDVersion::V2), "JANCRE010000006.1_5563658_5564462_R:1-458_+"),
+            ("hg_38:chr+1:10-40_+", "obfc", "pass", Some(IDVersion::V2), "hg_38:chr+1:10-40_+"),
+            ("hg-38:chr_1:10-40_+", "obfc", "pass", Some(IDVersion::V2), "hg-38:chr_1:10-40_+"),
+            //
+            // Special case a variant of Arians output for a time
+            ("chr13:51174547-51174560_R", "obfc", "pass", Some(IDVersion::V0), "chr13:51174547-51174560_-"),
+            // Unrecognizable Examples
+            //   Unrecognized ranges, returned as monolithic identifier in Undefined format,
+            //   or WholeSequence when there is no range-like suffix at all
+            ("chr1:100_200_R", "obfc", "pass", Some(IDVersion::Undefined), "chr1:100_200_R"),
+            ("chr1:200_100", "obfc", "pass", Some(IDVersion::Undefined), "chr1:200_100"),
+            ("chr1", "obfc", "pass", Some(IDVersion::WholeSequence), "chr1"),
+            ("hg38:chr1", "obfc", "pass", Some(IDVersion::WholeSequence), "hg38:chr1"),
+            ("100_200", "obfc", "pass", Some(IDVersion::WholeSequence), "100_200"),
+            ("100-200", "obfc", "pass", Some(IDVersion::WholeSequence), "100-200"),
+            ("100:200", "obfc", "pass", Some(IDVersion::WholeSequence), "100:200"),
+            ("100:200:", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("100:200:seq:", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            //   Failures due to invalid characters
+            ("seq1_ 1_2", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            ("seq1:1-2\n_+", "obfc", "fail", Some(IDVersion::Undefined), ""),
+        ]
+    }
+
+    #[test]
+    fn test_convert_id() {
+        for (id, coord_type, exp_outcome, exp_version, exp_v2_format) in convert_id_test_cases() {
+            run_convert_id_test(id, coord_type, exp_outcome, exp_version, exp_v2_format);
+        }
+    }
+
+fn run_parse_id_test(
+    id: &str,
+    expected_outcome: &str,
+    expected_assembly: &str,
+    expected_sequence_id: &str,
+    expected_ranges: Vec<(usize, usize, Strand)>,
+    expected_version: Option<IDVersion>,
+    expected_normalization: &str,
+) {
+    let result = Identifier::from_v2(id);
+
+    match (result.as_ref(), expected_outcome, expected_version.clone()) {
+        // Expected pass case
+        (Ok(parsed_id), "pass", Some(expected_version)) => {
+            println!("Parsed ID: {:?}", parsed_id); // Debug output for parsed ID
+            assert_eq!(
+                parsed_id.assembly_id.as_deref().unwrap_or(""),
+                expected_assembly,
+                "Assembly mismatch for ID: {}", id
+            );
+            assert_eq!(parsed_id.sequence_id, expected_sequence_id, "Sequence ID mismatch for ID: {}", id);
+            assert_eq!(parsed_id.inferred_version, expected_version, "IDVersion mismatch for ID: {}", id);
+
+            let parsed_ranges: Vec<(usize, usize, Strand)> = parsed_id
+                .ranges
+                .iter()
+                .map(|r| (r.start, r.end, r.orientation))
+                .collect();
+            assert_eq!(parsed_ranges, expected_ranges, "Range mismatch for ID: {}", id);
+
+            // Normalization check if expected_normalization is provided
+            if !expected_normalization.is_empty() {
+                match parsed_id.normalize() {
+                    Ok(normalized_id) => {
+                        assert_eq!(normalized_id.to_string(), expected_normalization, "Normalization mismatch for ID: {}", id);
+                    }
+                    Err(err) => {
+                        panic!("Normalization failed for ID: {}. Error: {}", id, err);
+                    }
+                }
+            }
+
+
+
+        }
+        // Expected failure case
+        (Err(err), "fail", None) => {
+            println!("Expected failure for ID: {}. Error message: {}", id, err);
+        }
+        // Unexpected success when failure was expected
+        (Ok(parsed_id), "fail", _) => {
+            panic!(
+                "Unexpected success for ID: {}. Expected outcome: fail, but got success with Assembly: '{:?}', Sequence ID: '{}', Ranges: {:?}, IDVersion: {:?}",
+                id, parsed_id.assembly_id, parsed_id.sequence_id, parsed_id.ranges, parsed_id.inferred_version
+            );
+        }
+        // Unexpected failure when pass was expected
+        (Err(err), "pass", Some(expected_version)) => {
+            panic!(
+                "Unexpected failure for ID: {}. Expected outcome: pass, version: {:?}. Error: {}",
+                id, expected_version, err
+            );
+        }
+        // Catch-all for any unexpected outcome
+        _ => panic!(
+            "Unexpected outcome for ID: {}. Expected outcome: {}, version: {:?}, but got result: {:?}",
+            id, expected_outcome, expected_version, result
+        ),
+    }
+}
+    #[test]
+    fn test_parse_id() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "pass", "", "chr1", vec![(100, 200, Strand::Forward)], Some(IDVersion::V2), "chr1:100-200_+"),
+            ("hg38:chr1:100-200_+", "pass", "hg38", "chr1", vec![(100, 200, Strand::Forward)], Some(IDVersion::V2), "hg38:chr1:100-200_+"),
+            ("hg38:chr1:100-200_-", "pass", "hg38", "chr1", vec![(100, 200, Strand::Reverse)], Some(IDVersion::V2), "hg38:chr1:100-200_-"),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "pass", "hg38", "chr1", vec![(100, 200, Strand::Forward), (10, 50, Strand::Reverse), (1, 5, Strand::Forward)], Some(IDVersion::V2), "hg38:chr1:145-149_-"),
+            ("hg38:chr1:200-100_-", "fail", "", "", vec![], None, ""),
+            ("100:200:", "fail", "", "", vec![], None, ""),
+            (":chr1", "fail", "", "", vec![], None, ""),
+            ("chr1:", "fail", "", "", vec![], None, ""),
+            (":chr1:100-200_+", "fail", "", "", vec![], None, ""),
+            ("hg_38:chr+1:10-40_+", "pass", "hg_38", "chr+1", vec![(10, 40, Strand::Forward)], Some(IDVersion::V2), "hg_38:chr+1:10-40_+"),
+            ("chr1:100-200", "pass", "", "chr1", vec![(100, 200, Strand::Forward)], Some(IDVersion::V2), "chr1:100-200_+"),
+            ("hg38:chr1:100-200:10-50_-", "pass", "hg38", "chr1", vec![(100, 200, Strand::Forward), (10, 50, Strand::Reverse)], Some(IDVersion::V2), "hg38:chr1:109-149_-"),
+            // Add remaining test cases here in the same format
+        ];
+
+        for (id, outcome, assembly, seq_id, ranges, version, normalization) in test_cases {
+            run_parse_id_test(id, outcome, assembly, seq_id, ranges, version, normalization);
+        }
+    }
+
+    #[test]
+    fn test_error_variants() {
+        let test_cases = vec![
+            ("seq1_ 1_2", SmittenError::WhitespaceInIdentifier { id: "seq1_ 1_2".to_string(), position: 5 }),
+            ("chr2_200_100", SmittenError::DecreasingRange { start: 200, end: 100 }),
+            ("HSPA2_0_0", SmittenError::ZeroCoordinate { start: 0, end: 0 }),
+            ("chr_1_100_200_150_200_R", SmittenError::SubrangeOutOfBounds { start: 150, end: 200, parent_len: 101 }),
+            ("AMM::1002:Seq1:100_200", SmittenError::InvalidAssemblyStructure { id: "AMM::1002:Seq1:100_200".to_string(), position: 4 }),
+        ];
+
+        for (id, expected) in test_cases {
+            let err = Identifier::from_unknown_format(id, false, false).unwrap_err();
+            assert_eq!(err, expected, "Error mismatch for ID: {}", id);
+        }
+
+        assert!(matches!(
+            Identifier::from_v2("hg38:chr1:200-100_-"),
+            Err(SmittenError::DecreasingRange { start: 200, end: 100 })
+        ));
+        assert!(matches!(Identifier::from_v2("hg38:"), Err(SmittenError::EmptySequenceId(_))));
+        assert_eq!(
+            SmittenError::ZeroCoordinate { start: 0, end: 10 }.to_string(),
+            "Invalid range 0-10 in a one-based fully-closed coordinate system."
+        );
+    }
+
+    #[test]
+    fn test_identifier_eq() {
+        let (v1_id, _) = Identifier::from_unknown_format("hg38:chr1:100-200:50-10", false, false).unwrap();
+        let v2_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-").unwrap();
+        assert_eq!(v1_id, v2_id);
+        assert_eq!(v1_id.normalize().unwrap(), v2_id.normalize().unwrap());
+        assert_eq!(v2_id.normalize().unwrap(), Identifier::from_v2("hg38:chr1:109-149_-").unwrap());
+
+        // inferred_version does not participate in equality
+        let (v0_id, version) = Identifier::from_unknown_format("chr1_100_200_R", false, false).unwrap();
+        let mut other = Identifier::from_v2("chr1:100-200_-").unwrap();
+        assert_eq!(version, IDVersion::V0);
+        other.inferred_version = IDVersion::Undefined;
+        assert_eq!(v0_id, other);
+
+        assert_ne!(v2_id, Identifier::from_v2("chr1:100-200_+:10-50_-").unwrap());
+        assert_ne!(v2_id, Identifier::from_v2("hg38:chr1:100-200_+:10-50_+").unwrap());
+    }
+
+    #[test]
+    fn test_identifier_hash() {
+        use std::collections::HashSet;
+
+        let ids = vec![
+            Identifier::from_v2("hg38:chr1:100-200_+").unwrap(),
+            Identifier::from_unknown_format("hg38:chr1_100_200", false, false).unwrap().0,
+            Identifier::from_unknown_format("hg38:chr1:100-200", false, false).unwrap().0,
+            Identifier::from_v2("hg38:chr1:100-200_-").unwrap(),
+            Identifier::from_v2("chr1:100-200_+").unwrap(),
+            Identifier::from_v2("chr1:100-200_+").unwrap(),
+        ];
+
+        let unique: HashSet<Identifier> = ids.into_iter().collect();
+        assert_eq!(unique.len(), 3);
+        assert!(unique.contains(&Identifier::from_v2("hg38:chr1:100-200_+").unwrap()));
+        assert!(unique.contains(&Identifier::from_v2("hg38:chr1:100-200_-").unwrap()));
+        assert!(unique.contains(&Identifier::from_v2("chr1:100-200_+").unwrap()));
+    }
+
+    #[test]
+    fn test_length() {
+        let test_cases = vec![
+            ("chr1", None),
+            ("hg38:chr1", None),
+            ("chr1:200-200_+", Some(1)),
+            ("chr1:100-200_-", Some(101)),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", Some(5)),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.length(), Ok(expected), "Length mismatch for ID: {}", id);
+        }
+
+        let full_width = Identifier::from_v2(&format!("chr1:0-{}_+", usize::MAX)).unwrap();
+        assert!(matches!(full_width.length(), Err(SmittenError::CoordinateOverflow(_))));
+    }
+
+    #[test]
+    fn test_from_str() {
+        fn parse_both(a: &str, b: &str) -> Result<(Identifier, Identifier), SmittenError> {
+            Ok((a.parse()?, b.parse()?))
+        }
+
+        let (id, _) = parse_both("hg38:chr1:100-200_+", "chr1").unwrap();
+        assert_eq!(id.assembly_id, Some("hg38".to_string()));
+        assert_eq!(id.sequence_id, "chr1");
+        assert_eq!(id.to_string(), "hg38:chr1:100-200_+");
+        assert_eq!(id.inferred_version, IDVersion::V2);
+
+        // The error is propagated through the `?` operator
+        assert!(parse_both("chr1:100-200_+", "hg38:chr1:200-100_-").is_err());
+    }
+
+    #[test]
+    fn test_is_whole_sequence() {
+        assert!(Identifier::from_v2("chr1").unwrap().is_whole_sequence());
+        assert!(Identifier::from_v2("hg38:chr1").unwrap().is_whole_sequence());
+        assert!(!Identifier::from_v2("chr1:100-200_+").unwrap().is_whole_sequence());
+        assert!(!Identifier::from_unknown_format("chr1_100_200", false, false).unwrap().0.is_whole_sequence());
+    }
+
+    #[test]
+    fn test_to_v1_string() {
+        // V1 examples from the module documentation
+        let test_cases = vec!["Chr1", "Seq1:10-30", "Seq1:30-10", "Seq1:100-200:10-30", "Seq1:100-200:30-10"];
+
+        for id in test_cases {
+            let (parsed_id, _) = Identifier::from_unknown_format(id, false, false).unwrap();
+            assert_eq!(parsed_id.to_v1_string().unwrap(), id, "V1 mismatch for ID: {}", id);
+        }
+
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-").unwrap();
+        assert_eq!(parsed_id.to_v1_string().unwrap(), "hg38:chr1:100-200:50-10");
+        assert_eq!(Identifier::from_v2("chr1:200-200_+").unwrap().to_v1_string().unwrap(), "chr1:200-200");
+
+        // The strand of a single base reverse range is lost in V1
+        assert!(matches!(
+            Identifier::from_v2("chr1:200-200_-").unwrap().to_v1_string(),
+            Err(SmittenError::UnrepresentableInVersion { version: IDVersion::V1, .. })
+        ));
+        for id in ["chr1_-", "hg38:chr1_+"] {
+            assert_eq!(
+                Identifier::from_v2_with_whole_strand(id).unwrap().to_v1_string(),
+                Err(SmittenError::UnrepresentableInVersion { id: id.to_string(), version: IDVersion::V1 })
+            );
+        }
+
+        // Chains V1 rejects, and names that read back differently
+        let unrepresentable = vec![
+            Identifier::from_v2("x:1-2_+:3-4_+").unwrap(),
+            Identifier::from_v2_parts(Some("hg38"), "chr1:5", ":100-200_+").unwrap(),
+        ];
+        for parsed_id in unrepresentable {
+            assert_eq!(
+                parsed_id.to_v1_string(),
+                Err(SmittenError::UnrepresentableInVersion { id: parsed_id.to_string(), version: IDVersion::V1 }),
+                "Expected V1 rejection for ID: {}",
+                parsed_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_all_versions() {
+        let test_cases = vec![
+            ("chr1:100-200_-", ("chr1_100_200_R", "chr1:200-100", "chr1:100-200_-")),
+            ("hg38:chr1:100-200_+:10-50_-", ("hg38:chr1_109_149_R", "hg38:chr1:149-109", "hg38:chr1:109-149_-")),
+            ("chr1:100-200_+", ("chr1_100_200", "chr1:100-200", "chr1:100-200_+")),
+            ("chr1", ("chr1", "chr1", "chr1")),
+        ];
+
+        for (id, (v0, v1, v2)) in test_cases {
+            let versions = Identifier::from_v2(id).unwrap().to_all_versions().unwrap();
+            assert_eq!(versions, (v0.to_string(), v1.to_string(), v2.to_string()), "Version mismatch for ID: {}", id);
+        }
+
+        assert!(matches!(
+            Identifier::from_v2("chr1:100-200_+:5-5_-").unwrap().to_all_versions(),
+            Err(SmittenError::UnrepresentableInVersion { version: IDVersion::V1, .. })
+        ));
+        assert!(matches!(
+            Identifier::from_v2("seq_100_200").unwrap().to_all_versions(),
+            Err(SmittenError::UnrepresentableInVersion { version: IDVersion::V0, .. })
+        ));
+        assert!(matches!(
+            Identifier::from_v2_with_whole_strand("chr1_-").unwrap().to_all_versions(),
+            Err(SmittenError::UnrepresentableInVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_to_v0_string() {
+        let test_cases = vec![
+            ("chr1", "chr1"),
+            ("chr1:100-200_+", "chr1_100_200"),
+            ("hg38:chr1:100-200_-", "hg38:chr1_100_200_R"),
+            ("chr1:11023-38232_-:100-200_+", "chr1_11023_38232_R_100_200"),
+            ("seq1_exon2:100-200_-", "seq1_exon2_100_200_R"),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.to_v0_string().unwrap(), expected, "V0 mismatch for ID: {}", id);
+        }
+
+        // Sequence identifiers that themselves look like a V0 range are ambiguous
+        let test_cases = vec!["seq_100_200", "seq_100_200:1-10_+", "seq_1_10_R"];
+        for id in test_cases {
+            assert!(matches!(
+                Identifier::from_v2(id).unwrap().to_v0_string(),
+                Err(SmittenError::UnrepresentableInVersion { version: IDVersion::V0, .. })
+            ), "Expected ambiguity failure for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_to_bed_coords() {
+        let test_cases = vec![
+            ("chr1", vec![]),
+            ("chr1:100-200_+", vec![(99, 200, Strand::Forward)]),
+            ("chr1:1-1_-", vec![(0, 1, Strand::Reverse)]),
+            ("hg38:chr1:100-200_+:10-50_-", vec![(99, 200, Strand::Forward), (9, 50, Strand::Reverse)]),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.to_bed_coords(), Ok(expected), "BED coordinate mismatch for ID: {}", id);
+        }
+
+        // Round trip with the zbho input flag
+        let (parsed_id, _) = Identifier::from_unknown_format("chr1:99-200", true, false).unwrap();
+        assert_eq!(parsed_id.to_bed_coords(), Ok(vec![(99, 200, Strand::Forward)]));
+
+        assert_eq!(
+            Identifier::from_v2("chr1:0-10_+").unwrap().to_bed_coords(),
+            Err(SmittenError::ZeroCoordinate { start: 0, end: 10 })
+        );
+    }
+
+    #[test]
+    fn test_to_bed() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "chr1\t99\t200\tchr1:100-200_+\t0\t+"),
+            ("hg38:chr1:100-200_-", "chr1\t99\t200\thg38:chr1:100-200_-\t0\t-"),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "chr1\t144\t149\thg38:chr1:100-200_+:10-50_-:1-5_+\t0\t-"),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.to_bed().unwrap(), expected, "BED mismatch for ID: {}", id);
+        }
+
+        assert_eq!(
+            Identifier::from_v2("hg38:chr1").unwrap().to_bed(),
+            Err(SmittenError::WholeSequence("hg38:chr1".to_string()))
+        );
+        assert_eq!(
+            Identifier::from_v2("chr1:0-10_+").unwrap().to_bed(),
+            Err(SmittenError::ZeroCoordinate { start: 0, end: 10 })
+        );
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let ids = vec!["chr1_100_200", "seq1_ 1_2", "chr1:200-1", "chr2_200_100", "hg38:chr1:100-200_+"];
+        let results = Identifier::parse_many(&ids, false);
+
+        assert_eq!(results.len(), ids.len());
+        for ((input, _), id) in results.iter().zip(&ids) {
+            assert_eq!(input, id);
+        }
+
+        let (_, first) = &results[0];
+        assert_eq!(first.as_ref().unwrap().0.to_string(), "chr1:100-200_+");
+        assert_eq!(first.as_ref().unwrap().1, IDVersion::V0);
+        assert!(matches!(results[1].1, Err(SmittenError::WhitespaceInIdentifier { .. })));
+        assert_eq!(results[2].1.as_ref().unwrap().1, IDVersion::V1);
+        assert!(matches!(results[3].1, Err(SmittenError::DecreasingRange { start: 200, end: 100 })));
+        assert_eq!(results[4].1.as_ref().unwrap().1, IDVersion::V2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_reader() {
+        use std::io::Cursor;
+
+        let input: &[u8] = b"chr1_100_200\n\nseq1_ 1_2\r\n  \t\nhg38:chr1:100-200_+\n";
+        let results: Vec<_> = Identifier::from_reader(Cursor::new(input), false).collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, 1);
+        assert_eq!(results[0].1.as_ref().unwrap().to_string(), "chr1:100-200_+");
+        assert_eq!(results[1].0, 3);
+        assert!(matches!(results[1].1, Err(SmittenError::WhitespaceInIdentifier { .. })));
+        assert_eq!(results[2].0, 5);
+        assert_eq!(results[2].1.as_ref().unwrap().to_string(), "hg38:chr1:100-200_+");
+    }
+
+    #[test]
+    fn test_reverse_complement() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "chr1:100-200_-"),
+            ("chr1:100-200_-", "chr1:100-200_+"),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "hg38:chr1:145-149_+"),
+            ("hg38:chr1", "hg38:chr1"),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            let rc_id = parsed_id.reverse_complement().unwrap();
+            assert_eq!(rc_id.to_string(), expected, "Reverse complement mismatch for ID: {}", id);
+            assert_eq!(rc_id.reverse_complement().unwrap(), parsed_id.normalize().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_absolute_range() {
+        let test_cases = vec![
+            ("chr1", None),
+            ("chr1:100-200_+", Some((100, 200, Strand::Forward))),
+            ("hg38:chr1:100-200_-", Some((100, 200, Strand::Reverse))),
+            ("hg38:chr1:100-200_+:10-50_-", Some((109, 149, Strand::Reverse))),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", Some((145, 149, Strand::Reverse))),
+            ("chr1:100-200_-:1-10_-", Some((191, 200, Strand::Forward))),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.absolute_range(), Ok(expected), "Absolute range mismatch for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_identifier_ord() {
+        let expected = vec![
+            "chr1",
+            "chr1:50-60_+",
+            "chr2:10-20_-",
+            "hg19:chr1:100-200_+",
+            "hg38:chr1",
+            "hg38:chr1:100-200_+",
+            "hg38:chr1:100-200_-",
+            "hg38:chr1:100-300_+",
+            "hg38:chr1:100-200_+:46-50_+",
+            "hg38:chr1:150-200_+",
+            "hg38:chr10:1-10_+",
+            "hg38:chr2:1-10_+",
+        ];
+
+        let mut ids: Vec<Identifier> = [7, 2, 11, 0, 5, 9, 3, 10, 1, 6, 4, 8]
+            .iter()
+            .map(|&i| Identifier::from_v2(expected[i]).unwrap())
+            .collect();
+        ids.sort();
+
+        let sorted: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn test_strand() {
+        assert_eq!("+".parse::<Strand>(), Ok(Strand::Forward));
+        assert_eq!("-".parse::<Strand>(), Ok(Strand::Reverse));
+        assert_eq!("R".parse::<Strand>(), Err(SmittenError::InvalidOrientation('R')));
+        assert_eq!("".parse::<Strand>(), Err(SmittenError::InvalidOrientation(' ')));
+
+        assert_eq!(Strand::Forward.to_string(), "+");
+        assert_eq!(Strand::Reverse.to_string(), "-");
+        assert_eq!(Strand::Forward.complement(), Strand::Reverse);
+        assert_eq!(Strand::Reverse.complement().complement(), Strand::Reverse);
+
+        let parsed_id = Identifier::from_v2("chr1:100-200_+:10-50_-").unwrap();
+        let chars: Vec<char> = parsed_id.ranges.iter().map(|r| r.orientation_char()).collect();
+        assert_eq!(chars, vec!['+', '-']);
+    }
+
+    #[test]
+    fn test_max_range_depth() {
+        let deep_id = format!("seq{}", ":1-2_+".repeat(MAX_RANGE_DEPTH + 1));
+        assert_eq!(Identifier::from_v2(&deep_id), Err(SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH)));
+        assert_eq!(
+            Identifier::from_unknown_format(&deep_id, false, false).unwrap_err(),
+            SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH)
+        );
+
+        let parsed_id = Identifier::parse_id_with_depth(&deep_id, MAX_RANGE_DEPTH + 1).unwrap();
+        assert_eq!(parsed_id.ranges.len(), MAX_RANGE_DEPTH + 1);
+
+        let max_id = format!("seq{}", ":1-2_+".repeat(MAX_RANGE_DEPTH));
+        assert!(Identifier::from_v2(&max_id).is_ok());
+        assert!(Identifier::from_unknown_format(&max_id, false, false).is_ok());
+    }
+
+    #[test]
+    fn test_project_child() {
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+").unwrap();
+        let child_id = parsed_id.project_child(10, 50, Strand::Reverse).unwrap();
+        assert_eq!(child_id.to_string(), "hg38:chr1:100-200_+:10-50_-");
+        assert_eq!(child_id.project_child(1, 41, Strand::Forward).unwrap().to_string(), "hg38:chr1:100-200_+:10-50_-:1-41_+");
+
+        // Whole-sequence identifiers have no known parent length
+        let root_id = Identifier::from_v2("chr1").unwrap();
+        assert_eq!(root_id.project_child(100, 200, Strand::Forward).unwrap().to_string(), "chr1:100-200_+");
+
+        assert_eq!(parsed_id.project_child(50, 10, Strand::Forward), Err(SmittenError::DecreasingRange { start: 50, end: 10 }));
+        assert_eq!(parsed_id.project_child(0, 10, Strand::Forward), Err(SmittenError::ZeroCoordinate { start: 0, end: 10 }));
+        assert_eq!(
+            parsed_id.project_child(90, 102, Strand::Forward),
+            Err(SmittenError::SubrangeOutOfBounds { start: 90, end: 102, parent_len: 101 })
+        );
+    }
+
+    #[test]
+    fn test_root() {
+        let test_cases = vec![
+            ("hg38:chr1:100-200_+", "hg38:chr1"),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "hg38:chr1"),
+            ("chr1:100-200_-", "chr1"),
+            ("chr1", "chr1"),
+        ];
+
+        for (id, expected) in test_cases {
+            let root_id = Identifier::from_v2(id).unwrap().root();
+            assert_eq!(root_id.to_string(), expected, "Root mismatch for ID: {}", id);
+            assert!(root_id.is_whole_sequence());
+            assert_eq!(root_id.inferred_version, IDVersion::V2);
+        }
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "chr1:300-400_+", false),
+            ("chr1:100-200_+", "chr1:201-300_+", false),
+            ("chr1:100-200_+", "chr1:200-300_-", true),
+            ("chr1:100-200_+", "chr1:120-150_+", true),
+            ("chr1:120-150_+", "chr1:100-200_+", true),
+            ("hg38:chr1:100-200_+:10-50_-", "hg38:chr1:140-160_+", true),
+            ("hg38:chr1:100-200_+:10-50_-", "hg38:chr1:150-160_+", false),
+            ("chr1", "chr1:100-200_+", true),
+        ];
+
+        for (id1, id2, expected) in test_cases {
+            let id1 = Identifier::from_v2(id1).unwrap();
+            let id2 = Identifier::from_v2(id2).unwrap();
+            assert_eq!(id1.overlaps(&id2).unwrap(), expected, "Overlap mismatch for {} and {}", id1, id2);
+        }
+
+        let test_cases = vec![("chr1:100-200_+", "chr2:100-200_+"), ("hg38:chr1:100-200_+", "hg19:chr1:100-200_+"), ("hg38:chr1:100-200_+", "chr1:100-200_+")];
+        for (id1, id2) in test_cases {
+            let id1 = Identifier::from_v2(id1).unwrap();
+            let id2 = Identifier::from_v2(id2).unwrap();
+            assert!(matches!(id1.overlaps(&id2), Err(SmittenError::RootMismatch { .. })));
+        }
+    }
+
+    #[test]
+    fn test_intersect() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "chr1:150-300_+", Some("chr1:150-200_+")),
+            ("chr1:100-200_-", "chr1:150-300_+", Some("chr1:150-200_+")),
+            ("chr1:100-200_-", "chr1:150-300_-", Some("chr1:150-200_-")),
+            ("chr1:100-200_+", "chr1:200-300_+", Some("chr1:200-200_+")),
+            ("chr1:100-200_+", "chr1:120-130_-", Some("chr1:120-130_+")),
+            ("chr1:100-200_+", "chr1:201-300_+", None),
+            ("hg38:chr1:100-200_+:10-50_-", "hg38:chr1:140-160_-", Some("hg38:chr1:140-149_-")),
+            ("chr1", "chr1:100-200_+:1-10_-", Some("chr1:100-109_-")),
+        ];
+
+        for (id1, id2, expected) in test_cases {
+            let id1 = Identifier::from_v2(id1).unwrap();
+            let id2 = Identifier::from_v2(id2).unwrap();
+            let result = id1.intersect(&id2).unwrap().map(|id| id.to_string());
+            assert_eq!(result.as_deref(), expected, "Intersection mismatch for {} and {}", id1, id2);
+        }
+
+        let id1 = Identifier::from_v2("chr1:100-200_+").unwrap();
+        let id2 = Identifier::from_v2("chr2:100-200_+").unwrap();
+        assert!(matches!(id1.intersect(&id2), Err(SmittenError::RootMismatch { .. })));
+    }
+
+    #[test]
+    fn test_contains() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "chr1:120-150_+", true),
+            ("chr1:100-200_+", "chr1:100-200_-", true),
+            ("chr1:100-200_+", "chr1:150-250_+", false),
+            ("chr1:120-150_+", "chr1:100-200_+", false),
+            ("hg38:chr1:100-200_+", "hg38:chr1:100-200_+:10-50_-", true),
+            ("chr1", "chr1:100-200_+", true),
+            ("chr1:100-200_+", "chr1", false),
+        ];
+
+        for (id1, id2, expected) in test_cases {
+            let id1 = Identifier::from_v2(id1).unwrap();
+            let id2 = Identifier::from_v2(id2).unwrap();
+            assert_eq!(id1.contains(&id2).unwrap(), expected, "Containment mismatch for {} and {}", id1, id2);
+        }
+
+        let id1 = Identifier::from_v2("hg38:chr1:100-200_+").unwrap();
+        let id2 = Identifier::from_v2("chr1:120-150_+").unwrap();
+        assert!(matches!(id1.contains(&id2), Err(SmittenError::RootMismatch { .. })));
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        let parse_all = |ids: &[&str]| -> Vec<Identifier> { ids.iter().map(|id| Identifier::from_v2(id).unwrap()).collect() };
+        let render_all = |ids: Vec<Identifier>| -> Vec<String> { ids.iter().map(|id| id.to_string()).collect() };
+
+        let ids = parse_all(&["chr1:150-300_+", "chr1:100-200_+", "chr1:301-400_+", "chr1:500-600_+", "chr2:1-10_+"]);
+        assert_eq!(
+            render_all(merge_ranges(&ids).unwrap()),
+            vec!["chr1:100-400_+", "chr1:500-600_+", "chr2:1-10_+"]
+        );
+
+        // Strands are merged separately unless requested otherwise
+        let ids = parse_all(&["hg38:chr1:100-200_+", "hg38:chr1:150-300_-", "hg38:chr1:250-400_-", "hg38:chr1:100-200_+:1-10_-"]);
+        assert_eq!(
+            render_all(merge_ranges(&ids).unwrap()),
+            vec!["hg38:chr1:100-109_-", "hg38:chr1:100-200_+", "hg38:chr1:150-400_-"]
+        );
+        assert_eq!(render_all(merge_ranges_with(&ids, true).unwrap()), vec!["hg38:chr1:100-400_+"]);
+
+        let ids = parse_all(&["chr1:150-300_-", "chr1:100-200_-"]);
+        assert_eq!(render_all(merge_ranges_with(&ids, true).unwrap()), vec!["chr1:100-300_-"]);
+
+        let ids = parse_all(&["chr1:100-200_+", "chr1"]);
+        assert_eq!(merge_ranges(&ids), Err(SmittenError::WholeSequence("chr1".to_string())));
+
+        let full_width = format!("chr1:1-{}_+", usize::MAX);
+        let ids = parse_all(&[&full_width, "chr1:5-10_+"]);
+        assert_eq!(render_all(merge_ranges(&ids).unwrap()), vec![full_width]);
+    }
+
+    #[test]
+    fn test_try_from() {
+        let from_str = Identifier::try_from("hg38:chr1:100-200_+:10-50_-").unwrap();
+        let from_string = Identifier::try_from("hg38:chr1:100-200_+:10-50_-".to_string()).unwrap();
+        assert_eq!(from_str, from_string);
+        assert_eq!(from_str.ranges.len(), 2);
+
+        assert!(matches!(Identifier::try_from("chr1:200-100_+"), Err(SmittenError::DecreasingRange { .. })));
+        assert!(matches!(Identifier::try_from("hg38:".to_string()), Err(SmittenError::EmptySequenceId(_))));
+
+        let rendered: String = from_string.into();
+        assert_eq!(rendered, "hg38:chr1:100-200_+:10-50_-");
+    }
+
+    #[test]
+    fn test_new() {
+        let id = Identifier::new(
+            Some("hg38".to_string()),
+            "chr1".to_string(),
+            vec![Range { start: 100, end: 200, orientation: Strand::Forward }, Range { start: 10, end: 50, orientation: Strand::Reverse }],
+        )
+        .unwrap();
+        assert_eq!(id, Identifier::from_v2("hg38:chr1:100-200_+:10-50_-").unwrap());
+        assert_eq!(id.inferred_version, IDVersion::V2);
+        assert_eq!(Identifier::new(None, "chr1".to_string(), vec![]).unwrap().to_string(), "chr1");
+
+        let test_cases = vec![
+            ("", vec![], SmittenError::EmptySequenceId(String::new())),
+            ("chr1", vec![Range { start: 200, end: 100, orientation: Strand::Forward }], SmittenError::DecreasingRange { start: 200, end: 100 }),
+            ("chr1", vec![Range { start: 0, end: 100, orientation: Strand::Forward }], SmittenError::ZeroCoordinate { start: 0, end: 100 }),
+            (
+                "chr1",
+                vec![Range { start: 100, end: 200, orientation: Strand::Forward }, Range { start: 90, end: 110, orientation: Strand::Forward }],
+                SmittenError::SubrangeOutOfBounds { start: 90, end: 110, parent_len: 101 },
+            ),
+        ];
+
+        for (sequence_id, ranges, expected) in test_cases {
+            assert_eq!(Identifier::new(None, sequence_id.to_string(), ranges), Err(expected));
+        }
+    }
+
+    #[test]
+    fn test_identifier_builder() {
+        let id = IdentifierBuilder::new()
+            .assembly("hg38")
+            .sequence("chr1")
+            .push_range(100, 200, Strand::Forward)
+            .and_then(|b| b.push_range(10, 50, Strand::Reverse))
+            .and_then(|b| b.push_range(1, 5, Strand::Forward))
+            .and_then(|b| b.build())
+            .unwrap();
+        assert_eq!(id, Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap());
+
+        let builder = IdentifierBuilder::new().sequence("chr1").push_range(100, 200, Strand::Forward).unwrap();
+        assert_eq!(
+            builder.push_range(50, 150, Strand::Forward).unwrap_err(),
+            SmittenError::SubrangeOutOfBounds { start: 50, end: 150, parent_len: 101 }
+        );
+
+        assert!(matches!(IdentifierBuilder::new().build(), Err(SmittenError::EmptySequenceId(_))));
+    }
+
+    #[test]
+    fn test_detect_version() {
+        for (id, _, exp_outcome, exp_version, _) in convert_id_test_cases() {
+            if exp_outcome == "pass" {
+                assert_eq!(Some(Identifier::detect_version(id)), exp_version, "IDVersion mismatch for ID: {}", id);
+            }
+        }
+
+        assert_eq!(Identifier::detect_version("seq1_ 1_2"), IDVersion::Undefined);
+        assert_eq!(Identifier::detect_version("hg38:chr1"), IDVersion::WholeSequence);
+        assert_eq!(Identifier::detect_version("chr1:200_100"), IDVersion::Undefined);
+        assert_eq!(Identifier::detect_version("chr2_200_100"), IDVersion::V0);
+
+        let max_id = format!("seq{}", ":1-2_+".repeat(MAX_RANGE_DEPTH));
+        assert_eq!(Identifier::detect_version(&max_id), IDVersion::V2);
+        let deep_id = format!("seq{}", ":1-2_+".repeat(MAX_RANGE_DEPTH + 1));
+        assert_eq!(Identifier::detect_version(&deep_id), IDVersion::Undefined);
+    }
+
+    #[test]
+    fn test_range_iterators() {
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap();
+
+        let outer: Vec<(usize, usize, Strand)> = parsed_id.ranges_outer_to_inner().map(|r| (r.start, r.end, r.orientation)).collect();
+        assert_eq!(outer, vec![(100, 200, Strand::Forward), (10, 50, Strand::Reverse), (1, 5, Strand::Forward)]);
+
+        let mut inner: Vec<&Range> = parsed_id.ranges_inner_to_outer().collect();
+        inner.reverse();
+        assert_eq!(inner, parsed_id.ranges_outer_to_inner().collect::<Vec<_>>());
+
+        assert_eq!(Identifier::from_v2("chr1").unwrap().ranges_inner_to_outer().count(), 0);
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let test_cases = vec![
+            ("AMM::1002:Seq1:100-200_+", 4),
+            ("AMM:1002:Seq1:100-200_+", 8),
+            ("a:b:c", 3),
+            ("Seq1:100-200_+\tscore=7", 14),
+            ("Seq1 :100-200_+", 4),
+        ];
+
+        for (id, expected) in test_cases {
+            let position = match Identifier::from_v2(id) {
+                Err(SmittenError::InvalidAssemblyStructure { position, .. }) => position,
+                Err(SmittenError::WhitespaceInIdentifier { position, .. }) => position,
+                other => panic!("Unexpected result for ID {}: {:?}", id, other),
+            };
+            assert_eq!(position, expected, "Position mismatch for ID: {}", id);
+            assert_eq!(Identifier::parse_id_ref(id).unwrap_err(), Identifier::from_v2(id).unwrap_err());
+        }
+
+        assert_eq!(
+            Identifier::from_unknown_format("AMM:1002::Seq1_100_200", false, false),
+            Err(SmittenError::InvalidAssemblyStructure { id: "AMM:1002::Seq1_100_200".to_string(), position: 8 })
+        );
+        assert_eq!(
+            Identifier::from_unknown_format(":chr1_1_10", false, false),
+            Err(SmittenError::InvalidAssemblyStructure { id: ":chr1_1_10".to_string(), position: 0 })
+        );
+        assert!(Identifier::from_v2("AMM::1002:Seq1").unwrap_err().to_string().contains("at byte 4"));
+    }
+
+    #[test]
+    fn test_idversion_default() {
+        assert_eq!(IDVersion::default(), IDVersion::Undefined);
+    }
+
+    #[test]
+    fn test_empty_components() {
+        let test_cases = vec![
+            ("100:200:", SmittenError::InvalidAssemblyStructure { id: "100:200:".to_string(), position: 7 }),
+            (":chr1", SmittenError::EmptyAssemblyId(":chr1".to_string())),
+            ("chr1:", SmittenError::EmptySequenceId("chr1:".to_string())),
+        ];
+
+        for (id, expected) in test_cases {
+            assert_eq!(Identifier::from_v2(id), Err(expected), "Error mismatch for ID: {}", id);
+            assert!(Identifier::from_unknown_format(id, false, false).is_err(), "Expected failure for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_same_span() {
+        let test_cases = vec![
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "hg38:chr1:145-149_-", true),
+            ("hg38:chr1:100-200_+:10-50_-", "hg38:chr1:100-200_+:10-50_-", true),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "hg38:chr1:145-149_+", false),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "chr1:145-149_-", false),
+            ("chr1", "chr1", true),
+        ];
+
+        for (id1, id2, expected) in test_cases {
+            let id1 = Identifier::from_v2(id1).unwrap();
+            let id2 = Identifier::from_v2(id2).unwrap();
+            assert_eq!(id1.same_span(&id2).unwrap(), expected, "Span mismatch for {} and {}", id1, id2);
+        }
+
+        let (v1_id, _) = Identifier::from_unknown_format("hg38:chr1:100-200:50-10", false, false).unwrap();
+        assert!(v1_id.same_span(&Identifier::from_v2("hg38:chr1:109-149_-").unwrap()).unwrap());
+        assert_ne!(v1_id, Identifier::from_v2("hg38:chr1:109-149_-").unwrap());
+    }
+
+    #[test]
+    fn test_from_v2_parts() {
+        let parsed_id = Identifier::from_v2_parts(Some("AMM::1002"), "Seq1:a", ":100-200_+:10-50_-").unwrap();
+        assert_eq!(parsed_id.assembly_id.as_deref(), Some("AMM::1002"));
+        assert_eq!(parsed_id.sequence_id, "Seq1:a");
+        assert_eq!(parsed_id.ranges, vec![Range { start: 100, end: 200, orientation: Strand::Forward }, Range { start: 10, end: 50, orientation: Strand::Reverse }]);
+
+        let parsed_id = Identifier::from_v2_parts(None, "chr:1", "").unwrap();
+        assert!(parsed_id.is_whole_sequence());
+        assert_eq!(parsed_id.sequence_id, "chr:1");
+
+        assert_eq!(
+            Identifier::from_v2_parts(None, "chr1", "100-200_+"),
+            Err(SmittenError::InvalidRangeSuffix("100-200_+".to_string()))
+        );
+        assert_eq!(
+            Identifier::from_v2_parts(None, "chr1", ":100-200_+x"),
+            Err(SmittenError::InvalidRangeSuffix(":100-200_+x".to_string()))
+        );
+        assert!(matches!(Identifier::from_v2_parts(None, "", ":1-2_+"), Err(SmittenError::EmptySequenceId(_))));
+    }
+
+    #[test]
+    fn test_range_lengths() {
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap();
+        assert_eq!(parsed_id.range_lengths(), Ok(vec![101, 41, 5]));
+        assert_eq!(Identifier::from_v2("chr1").unwrap().range_lengths(), Ok(Vec::new()));
+
+        let full_width = Identifier::from_v2(&format!("chr1:0-{}_+:1-5_+", usize::MAX)).unwrap();
+        assert!(matches!(full_width.range_lengths(), Err(SmittenError::CoordinateOverflow(_))));
+    }
+
+    #[test]
+    fn test_fold_innermost() {
+        let test_cases = vec![
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "hg38:chr1:100-200_+:46-50_-"),
+            ("hg38:chr1:100-200_+:46-50_-", "hg38:chr1:145-149_-"),
+            ("chr1:100-200_-:1-10_-", "chr1:191-200_+"),
+            ("chr1:100-200_+", "chr1:100-200_+"),
+            ("chr1", "chr1"),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.fold_innermost().unwrap().to_string(), expected, "Fold mismatch for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_from_v2_strict() {
+        let test_cases = vec!["(ACC)n#Simple:1-10_+", "hg_38:chr+1:10-40_+", "hg-38:chr_1", "seq1@*@)(_:1-200_+", "a'b\"c:x<y>z.%"];
+        for id in test_cases {
+            assert_eq!(Identifier::from_v2_strict(id), Identifier::from_v2(id), "Strict mismatch for ID: {}", id);
+        }
+
+        assert_eq!(
+            Identifier::from_v2_strict("chr\u{7}1:1-10_+"),
+            Err(SmittenError::InvalidCharacter { id: "chr\u{7}1:1-10_+".to_string(), character: '\u{7}' })
+        );
+        assert!(matches!(
+            Identifier::from_v2_strict("hg38:chré:1-10_+"),
+            Err(SmittenError::InvalidCharacter { character: 'é', .. })
+        ));
+        assert!(Identifier::from_v2("chr\u{7}1:1-10_+").is_ok());
+    }
+
+    #[test]
+    fn test_normalize_mut() {
+        let test_cases = vec!["hg38:chr1:100-200_+:10-50_-:1-5_+", "chr1:100-200_-:1-10_-", "chr1:100-200_+", "hg38:chr1"];
+
+        for id in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            let mut mutated_id = parsed_id.clone();
+            mutated_id.normalize_mut().unwrap();
+            assert_eq!(mutated_id.to_string(), parsed_id.normalize().unwrap().to_string(), "Normalization mismatch for ID: {}", id);
+            assert!(mutated_id.ranges.len() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_display_alternate() {
+        let test_cases = vec![
+            ("hg38:chr1:100-200_+:10-50_-", "hg38:chr1:100-200_+:10-50_-", "chr1:100-200_+:10-50_-"),
+            ("hg38:chr1", "hg38:chr1", "chr1"),
+            ("chr1:100-200_+", "chr1:100-200_+", "chr1:100-200_+"),
+        ];
+
+        for (id, expected, expected_alternate) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(format!("{}", parsed_id), expected);
+            assert_eq!(format!("{:#}", parsed_id), expected_alternate);
+        }
+    }
+
+    #[test]
+    fn test_outer_inner_range() {
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap();
+        assert_eq!(parsed_id.outermost_range(), Some(&Range { start: 100, end: 200, orientation: Strand::Forward }));
+        assert_eq!(parsed_id.innermost_range(), Some(&Range { start: 1, end: 5, orientation: Strand::Forward }));
+
+        let parsed_id = Identifier::from_v2("chr1:100-200_-").unwrap();
+        assert_eq!(parsed_id.outermost_range(), parsed_id.innermost_range());
+
+        let parsed_id = Identifier::from_v2("chr1").unwrap();
+        assert_eq!(parsed_id.outermost_range(), None);
+        assert_eq!(parsed_id.innermost_range(), None);
+    }
+
+    #[test]
+    fn test_normalize_overflow() {
+        // The parent range length does not fit in a usize
+        let parsed_id = Identifier::from_v2(&format!("chr1:0-{}_+:1-5_+", usize::MAX)).unwrap();
+        assert!(matches!(parsed_id.normalize(), Err(SmittenError::CoordinateOverflow(_))));
+
+        let parsed_id = Identifier::from_v2(&format!("chr1:{}-{}_-:1-11_+", usize::MAX - 10, usize::MAX)).unwrap();
+        assert_eq!(parsed_id.absolute_range(), Ok(Some((usize::MAX - 10, usize::MAX, Strand::Reverse))));
+
+        // Deliberately inconsistent chains
+        let test_cases = vec![
+            ("chr1:100-200_-:150-300_+", SmittenError::SubrangeOutOfBounds { start: 150, end: 300, parent_len: 101 }),
+            ("chr1:100-200_+:150-300_+", SmittenError::SubrangeOutOfBounds { start: 150, end: 300, parent_len: 101 }),
+            ("chr1:100-200_-:0-10_+", SmittenError::ZeroCoordinate { start: 0, end: 10 }),
+        ];
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.normalize(), Err(expected.clone()), "Error mismatch for ID: {}", id);
+            assert_eq!(parsed_id.fold_innermost(), Err(expected), "Error mismatch for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_from_v2_lenient() {
+        let test_cases = vec![
+            ("chr1:1,000-2,000_+", "chr1:1000-2000_+"),
+            ("hg38:chr1:1,000,000-2,000,000_-:1-1,000_+", "hg38:chr1:1000000-2000000_-:1-1000_+"),
+            ("chr,1:1,000-2,000_+", "chr,1:1000-2000_+"),
+            ("chr1:100-200_+", "chr1:100-200_+"),
+            // Not a well formed thousands separated coordinate, so part of the name
+            ("chr1_1,00-2,00", "chr1_1,00-2,00"),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2_lenient(id).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "Lenient parse mismatch for ID: {}", id);
+        }
+
+        // The strict parser does not recognize the range
+        let parsed_id = Identifier::from_v2("chr1:1,000-2,000_+").unwrap();
+        assert!(parsed_id.is_whole_sequence());
+        assert_eq!(parsed_id.sequence_id, "1,000-2,000_+");
+
+        let deep_id = format!("chr1{}", ":1,000-2,000_+".repeat(MAX_RANGE_DEPTH + 1));
+        assert_eq!(Identifier::from_v2_lenient(&deep_id), Err(SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH)));
+    }
+
+    #[test]
+    fn test_from_v2_autoorient() {
+        let test_cases = vec![
+            ("chr1:200-100_-", "chr1:100-200_-"),
+            ("hg38:chr1:100-200_+:50-10_-", "hg38:chr1:100-200_+:10-50_-"),
+            ("chr1:100-200_-", "chr1:100-200_-"),
+            ("chr1:5-5_-", "chr1:5-5_-"),
+            ("chr1", "chr1"),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2_autoorient(id).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "Auto-orient mismatch for ID: {}", id);
+        }
+
+        let rejected = vec!["chr1:200-100_+", "chr1:200-100", "chr1:300-100_-:50-10_+"];
+        for id in rejected {
+            assert!(
+                matches!(Identifier::from_v2_autoorient(id), Err(SmittenError::DecreasingRange { .. })),
+                "Expected a decreasing range error for ID: {}",
+                id
+            );
+        }
+        assert!(Identifier::from_v2("chr1:200-100_-").is_err());
+
+        let deep_id = format!("chr1{}", ":2-1_-".repeat(MAX_RANGE_DEPTH + 1));
+        assert_eq!(Identifier::from_v2_autoorient(&deep_id), Err(SmittenError::RangeDepthExceeded(MAX_RANGE_DEPTH)));
+    }
+
+    #[test]
+    fn test_non_ascii_digits() {
+        // Arabic-Indic digits are not coordinates, so the suffix is part of the name
+        let parsed_id = Identifier::from_v2("chr1:١-٥_+").unwrap();
+        assert!(parsed_id.is_whole_sequence());
+        assert_eq!(parsed_id.sequence_id, "١-٥_+");
+        assert_eq!(
+            Identifier::from_v2_strict("chr1:١-٥_+"),
+            Err(SmittenError::InvalidCharacter { id: "chr1:١-٥_+".to_string(), character: '١' })
+        );
+        assert_eq!(Identifier::parse_id_ref("chr1:١-٥_+").map(|id| id.to_owned()), Identifier::from_v2("chr1:١-٥_+"));
+
+        let ids = ["chr1_١_٥", "chr1:١-٥", "chr1:١,٠٠٠-٢,٠٠٠_+"];
+        for id in ids {
+            assert!(
+                !matches!(Identifier::from_unknown_format(id, false, false), Err(SmittenError::CoordinateOverflow(_))),
+                "Unexpected coordinate overflow for ID: {}",
+                id
+            );
+            assert!(Identifier::from_v2_lenient(id).unwrap().is_whole_sequence(), "Expected a whole sequence for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_canonical() {
+        let inputs = vec![
+            Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap(),
+            Identifier::from_v2("hg38:chr1:100-200_+:46-50_-").unwrap(),
+            Identifier::from_v2("hg38:chr1:145-149_-").unwrap(),
+            Identifier::from_unknown_format("hg38:chr1:100-200:50-46", false, false).unwrap().0,
+            Identifier::from_unknown_format("hg38:chr1_145_149_R", false, false).unwrap().0,
+        ];
+
+        for id in &inputs {
+            assert_eq!(id.canonical().unwrap(), "hg38:chr1:145-149_-", "Canonical mismatch for ID: {}", id);
+            assert!(id.same_span(&inputs[0]).unwrap());
+        }
+
+        assert_eq!(Identifier::from_v2("hg38:chr1").unwrap().canonical().unwrap(), "hg38:chr1");
+    }
+
+    #[test]
+    fn test_to_gff_fields() {
+        let test_cases = vec![
+            ("chr1:100-200_+", ("chr1", 100, 200, Strand::Forward)),
+            ("hg38:chr1:100-200_-", ("chr1", 100, 200, Strand::Reverse)),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", ("chr1", 145, 149, Strand::Reverse)),
+        ];
+
+        for (id, (seqid, start, end, strand)) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.to_gff_fields().unwrap(), (seqid.to_string(), start, end, strand), "GFF mismatch for ID: {}", id);
+        }
+
+        assert_eq!(
+            Identifier::from_v2("hg38:chr1").unwrap().to_gff_fields(),
+            Err(SmittenError::WholeSequence("hg38:chr1".to_string()))
+        );
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        // Names start with a letter so that they can never be mistaken for a range
+        const NAME_PATTERN: &str = r"[A-Za-z][A-Za-z0-9/?#\[\]@!$&'()*+,;=~|^<>.%_-]{0,15}";
+
+        fn arb_ranges() -> impl Strategy<Value = Vec<Range>> {
+            prop::collection::vec((any::<u32>(), any::<u32>(), any::<bool>()), 0..6).prop_map(|seeds| {
+                let mut ranges = Vec::new();
+                let mut parent_len: usize = 1_000_000_000;
+                for (a, b, reverse) in seeds {
+                    let x = a as usize % parent_len + 1;
+                    let y = b as usize % parent_len + 1;
+                    let (start, end) = if x <= y { (x, y) } else { (y, x) };
+                    ranges.push(Range { start, end, orientation: if reverse { Strand::Reverse } else { Strand::Forward } });
+                    parent_len = end - start + 1;
+                }
+                ranges
+            })
+        }
+
+        fn arb_identifier() -> impl Strategy<Value = Identifier> {
+            (prop::option::of(NAME_PATTERN), NAME_PATTERN, arb_ranges()).prop_map(|(assembly_id, sequence_id, ranges)| {
+                Identifier::new(assembly_id, sequence_id, ranges).unwrap()
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn test_v2_round_trip(id in arb_identifier()) {
+                let parsed_id = Identifier::from_v2(&id.to_string()).unwrap();
+                prop_assert_eq!(&parsed_id, &id);
+                prop_assert!(parsed_id.normalize().is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift() {
+        let test_cases = vec![
+            ("chr1:100-200_+", 50, "chr1:150-250_+"),
+            ("chr1:100-200_+", -99, "chr1:1-101_+"),
+            ("hg38:chr1:100-200_+:10-50_-", -50, "hg38:chr1:50-150_+:10-50_-"),
+            ("chr1:100-200_-", 0, "chr1:100-200_-"),
+        ];
+
+        for (id, delta, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.shift(delta).unwrap().to_string(), expected, "Shift mismatch for ID: {}", id);
+        }
+
+        let parsed_id = Identifier::from_v2("chr1:100-200_+").unwrap();
+        assert!(matches!(parsed_id.shift(-100), Err(SmittenError::CoordinateOverflow(_))));
+        assert!(matches!(parsed_id.shift(i64::MIN), Err(SmittenError::CoordinateOverflow(_))));
+        assert_eq!(Identifier::from_v2("chr1").unwrap().shift(1), Err(SmittenError::WholeSequence("chr1".to_string())));
+    }
+
+    #[test]
+    fn test_clamp_to_length() {
+        let test_cases = vec![
+            ("chr1:100-200_+", 1000, "chr1:100-200_+"),
+            ("chr1:100-200_-", 200, "chr1:100-200_-"),
+            ("chr1:100-200_-", 150, "chr1:100-150_-"),
+            ("hg38:chr1:100-200_+:10-50_-", 120, "hg38:chr1:109-120_-"),
+            ("chr1", 150, "chr1"),
+        ];
+
+        for (id, seq_len, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.clamp_to_length(seq_len).unwrap().to_string(), expected, "Clamp mismatch for ID: {}", id);
+        }
+
+        assert_eq!(
+            Identifier::from_v2("chr1:100-200_+").unwrap().clamp_to_length(99),
+            Err(SmittenError::SubrangeOutOfBounds { start: 100, end: 200, parent_len: 99 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_group_by_root() {
+        let ids: Vec<Identifier> = ["hg38:chr1:100-200_+", "hg38:chr2:1-10_+", "hg19:chr1:5-10_-", "hg38:chr1:300-400_-", "chr1", "hg38:chr1"]
+            .iter()
+            .map(|id| Identifier::from_v2(id).unwrap())
+            .collect();
+
+        let groups = group_by_root(ids);
+        assert_eq!(groups.len(), 4);
+
+        let render = |key: (Option<&str>, &str)| -> Vec<String> {
+            groups[&(key.0.map(|a| a.to_string()), key.1.to_string())].iter().map(|id| id.to_string()).collect()
+        };
+        assert_eq!(render((Some("hg38"), "chr1")), vec!["hg38:chr1:100-200_+", "hg38:chr1:300-400_-", "hg38:chr1"]);
+        assert_eq!(render((Some("hg38"), "chr2")), vec!["hg38:chr2:1-10_+"]);
+        assert_eq!(render((Some("hg19"), "chr1")), vec!["hg19:chr1:5-10_-"]);
+        assert_eq!(render((None, "chr1")), vec!["chr1"]);
+    }
+
+    #[test]
+    fn test_from_unknown_format_cs() {
+        for (id, coord_type, exp_outcome, exp_version, exp_v2_format) in convert_id_test_cases() {
+            let coord_system = match coord_type {
+                "zbho" => CoordSystem::ZeroBasedHalfOpen,
+                _ => CoordSystem::OneBasedFullyClosed,
+            };
+            let result = Identifier::from_unknown_format_cs(id, coord_system, false);
+            assert_eq!(result, Identifier::from_unknown_format(id, coord_type == "zbho", false), "Result mismatch for ID: {}", id);
+
+            if exp_outcome == "pass" {
+                let (converted_id, version) = result.unwrap();
+                assert_eq!(Some(version), exp_version, "IDVersion mismatch for ID: {}", id);
+                assert_eq!(converted_id.to_string(), exp_v2_format, "V2 format mismatch for ID: {}", id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_coordinate_overflow() {
+        let huge = "1234567890123456789012345";
+        let cases = vec![
+            format!("chr1:1-{}", huge),
+            format!("chr1_{}_{}", huge, huge),
+            format!("chr1:{}-{}", huge, huge),
+        ];
+
+        for id in &cases {
+            let result = Identifier::from_unknown_format(id, false, false);
+            assert!(matches!(result, Err(SmittenError::CoordinateOverflow(_))), "Expected overflow for ID: {}", id);
+        }
+
+        assert!(matches!(
+            Identifier::from_v2(&format!("chr1:1-{}_+", huge)),
+            Err(SmittenError::CoordinateOverflow(_))
+        ));
+        assert!(matches!(
+            Identifier::from_unknown_format(&format!("chr1_{}_{}", usize::MAX, usize::MAX), true, false),
+            Err(SmittenError::CoordinateOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_valid_v2() {
+        let test_cases = vec![
+            ("chr1:100-200_+", true),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", true),
+            ("hg_38:chr+1:10-40_+", true),
+            ("chr1:100-200", true),
+            ("chr1", true),
+            ("hg38:chr1:200-100_-", false),
+            ("100:200:", false),
+            (":chr1", false),
+            ("chr1:", false),
+            (":chr1:100-200_+", false),
+            ("chr 1:100-200_+", false),
+        ];
+
+        for (id, expected) in test_cases {
+            assert_eq!(Identifier::is_valid_v2(id), expected, "Validity mismatch for ID: {}", id);
+            assert_eq!(Identifier::is_valid_v2(id), Identifier::from_v2(id).is_ok(), "Disagreement with from_v2 for ID: {}", id);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_within_lengths() {
+        let lengths: HashMap<String, usize> = [("chr1".to_string(), 1000), ("chr2".to_string(), 50)].into_iter().collect();
+        let test_cases = vec![
+            ("hg38:chr1:100-200_+", true),
+            ("chr1:1-1000_-", true),
+            ("chr1:900-1001_+", false),
+            ("chr1:1-2000_+:100-200_-", true),
+            ("chr2:10-60_+", false),
+            ("chr1:0-10_+", false),
+            ("chr2", true),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.within_lengths(&lengths), Ok(expected), "Bounds mismatch for ID: {}", id);
+        }
+
+        assert_eq!(
+            Identifier::from_v2("chrX:1-10_+").unwrap().within_lengths(&lengths),
+            Err(SmittenError::UnknownSequence("chrX".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_with_assembly() {
+        let id = Identifier::from_v2("chr1").unwrap();
+        let with = id.clone().with_assembly("hg38");
+        assert_eq!(with.to_string(), "hg38:chr1");
+        assert_eq!(with, Identifier::from_v2("hg38:chr1").unwrap());
+        assert_eq!(with.clone().without_assembly(), id);
+        assert_eq!(with.without_assembly().to_string(), "chr1");
+
+        let id = Identifier::from_v2("hg19:chr1:100-200_+").unwrap();
+        assert_eq!(id.clone().with_assembly("hg38").to_string(), "hg38:chr1:100-200_+");
+        assert_eq!(id.clone().without_assembly().with_assembly(String::from("mm10")).to_string(), "mm10:chr1:100-200_+");
+        assert_eq!(id.without_assembly().without_assembly().to_string(), "chr1:100-200_+");
+    }
+
+    #[test]
+    fn test_annotation() {
+        let id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-").unwrap();
+        assert_eq!(id.annotation(), None);
+
+        let annotated = id.clone().with_annotation("W");
+        assert_eq!(annotated.annotation(), Some("W"));
+        assert_eq!(annotated.clone().annotation(), Some("W"));
+        assert_eq!(annotated.to_string(), id.to_string());
+        assert_eq!(annotated, id);
+        assert_eq!(annotated.normalize().unwrap().annotation(), Some("W"));
+        assert_eq!(annotated.with_annotation("N").annotation(), Some("N"));
+
+        let gap = Identifier::from_v2("chr1").unwrap().with_annotation("N");
+        assert_eq!(gap.to_string(), "chr1");
+        assert_eq!(gap.normalize().unwrap().annotation(), Some("N"));
+    }
+
+    #[test]
+    fn test_distance() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "chr1:250-300_+", 49),
+            ("chr1:250-300_+", "chr1:100-200_-", 49),
+            ("chr1:100-200_+", "chr1:201-300_+", 0),
+            ("chr1:100-200_+", "chr1:200-300_+", -1),
+            ("chr1:100-200_+", "chr1:150-300_-", -51),
+            ("chr1:100-200_+", "chr1:120-130_+", -11),
+            ("chr1:100-200_+:1-10_+", "chr1:120-130_+", 10),
+        ];
+
+        for (left, right, expected) in test_cases {
+            let left = Identifier::from_v2(left).unwrap();
+            let right = Identifier::from_v2(right).unwrap();
+            assert_eq!(left.distance(&right), Ok(expected), "Distance mismatch for {} and {}", left, right);
+            assert_eq!(right.distance(&left), Ok(expected), "Distance mismatch for {} and {}", right, left);
+        }
+
+        let id = Identifier::from_v2("chr1:100-200_+").unwrap();
+        assert!(matches!(
+            id.distance(&Identifier::from_v2("chr2:100-200_+").unwrap()),
+            Err(SmittenError::RootMismatch { .. })
+        ));
+        assert!(matches!(
+            id.distance(&Identifier::from_v2("chr1").unwrap()),
+            Err(SmittenError::WholeSequence(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_adjacent() {
+        let test_cases = vec![
+            ("chr1:100-200_+", "chr1:201-300_+", true),
+            ("chr1:100-200_+", "chr1:201-300_-", true),
+            ("chr1:100-200_+:1-10_+", "chr1:110-120_+", true),
+            ("chr1:100-200_+", "chr1:200-300_+", false),
+            ("chr1:100-200_+", "chr1:150-160_+", false),
+            ("chr1:100-200_+", "chr1:202-300_+", false),
+            ("chr1:100-200_+", "chr1", false),
+        ];
+
+        for (left, right, expected) in test_cases {
+            let left = Identifier::from_v2(left).unwrap();
+            let right = Identifier::from_v2(right).unwrap();
+            assert_eq!(left.is_adjacent(&right), Ok(expected), "Adjacency mismatch for {} and {}", left, right);
+            assert_eq!(right.is_adjacent(&left), Ok(expected), "Adjacency mismatch for {} and {}", right, left);
+        }
+
+        let id = Identifier::from_v2("chr1:100-200_+").unwrap();
+        assert!(matches!(
+            id.is_adjacent(&Identifier::from_v2("chr2:201-300_+").unwrap()),
+            Err(SmittenError::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_windows() {
+        let test_cases = vec![
+            ("chr1:101-130_+", 10, 10, vec!["1-10", "11-20", "21-30"]),
+            ("chr1:101-125_+", 10, 10, vec!["1-10", "11-20", "21-25"]),
+            ("chr1:101-120_+", 10, 5, vec!["1-10", "6-15", "11-20"]),
+            ("chr1:101-120_+", 4, 8, vec!["1-4", "9-12", "17-20"]),
+            ("chr1:101-105_+", 10, 3, vec!["1-5"]),
+            ("chr1:1000-2000_+:101-130_-", 15, 15, vec!["1-15", "16-30"]),
+        ];
+
+        for (id, size, step, expected) in test_cases {
+            let id = Identifier::from_v2(id).unwrap();
+            let parent = id.normalize().unwrap();
+            let windows = id.windows(size, step).unwrap();
+            let expected: Vec<String> = expected.iter().map(|range| format!("{}:{}_+", parent, range)).collect();
+            let actual: Vec<String> = windows.iter().map(|window| window.to_string()).collect();
+            assert_eq!(actual, expected, "Window mismatch for ID: {}", id);
+        }
+
+        let id = Identifier::from_v2("chr1:101-130_+").unwrap();
+        assert_eq!(id.windows(0, 10), Err(SmittenError::InvalidWindow { size: 0, step: 10 }));
+        assert_eq!(id.windows(10, 0), Err(SmittenError::InvalidWindow { size: 10, step: 0 }));
+        assert!(matches!(Identifier::from_v2("chr1").unwrap().windows(10, 10), Err(SmittenError::WholeSequence(_))));
+    }
+
+    #[test]
+    fn test_from_unknown_format_trimmed() {
+        let test_cases = vec![
+            ("  chr1_100_200  ", "chr1:100-200_+", IDVersion::V0),
+            ("\tchr1:100-200\r\n", "chr1:100-200_+", IDVersion::V1),
+            ("hg38:chr1:100-200_-", "hg38:chr1:100-200_-", IDVersion::V2),
+        ];
+
+        for (id, expected, expected_version) in test_cases {
+            let (parsed_id, version) = Identifier::from_unknown_format_trimmed(id, false).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "V2 format mismatch for ID: {:?}", id);
+            assert_eq!(version, expected_version, "IDVersion mismatch for ID: {:?}", id);
+        }
+
+        assert_eq!(
+            Identifier::from_unknown_format_trimmed(" chr1_100_200 ", true).unwrap().0.to_string(),
+            "chr1:101-200_+"
+        );
+        assert_eq!(
+            Identifier::from_unknown_format_trimmed("seq1_ 1_2", false),
+            Err(SmittenError::WhitespaceInIdentifier { id: "seq1_ 1_2".to_string(), position: 5 })
+        );
+        assert!(Identifier::from_unknown_format("  chr1_100_200  ", false, false).is_err());
+    }
+
+    #[test]
+    fn test_root_keys() {
+        let test_cases = vec![
+            ("hg38:chr1:100-200_+:10-50_-", "chr1", "hg38:chr1"),
+            ("hg38:chr1", "chr1", "hg38:chr1"),
+            ("chr1:100-200_+", "chr1", "chr1"),
+            ("chr1", "chr1", "chr1"),
+        ];
+
+        for (id, sequence_key, full_root_key) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.sequence_key(), sequence_key, "Sequence key mismatch for ID: {}", id);
+            assert_eq!(parsed_id.full_root_key(), full_root_key, "Root key mismatch for ID: {}", id);
+            assert_eq!(parsed_id.full_root_key(), parsed_id.root().to_string());
+        }
+    }
+
+    #[test]
+    fn test_from_fasta_header() {
+        let test_cases = vec![
+            (">hg38:chr1:100-200_+ description text here", "hg38:chr1:100-200_+", Some("description text here")),
+            (">hg38:chr1:100-200_+", "hg38:chr1:100-200_+", None),
+            ("chr1_100_200_R\tfrom RepeatMasker\n", "chr1:100-200_-", Some("from RepeatMasker")),
+            ("chr1:100-200 ", "chr1:100-200_+", None),
+        ];
+
+        for (header, expected, description) in test_cases {
+            let (parsed_id, parsed_description) = Identifier::from_fasta_header(header, false).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "Identifier mismatch for header: {:?}", header);
+            assert_eq!(parsed_description.as_deref(), description, "Description mismatch for header: {:?}", header);
+        }
+
+        assert_eq!(
+            Identifier::from_fasta_header(">chr1_100_200 desc", true).unwrap().0.to_string(),
+            "chr1:101-200_+"
+        );
+        assert!(Identifier::from_fasta_header(">", false).is_err());
+        assert!(Identifier::from_fasta_header(">chr2_200_100 desc", false).is_err());
+    }
+
+    #[test]
+    fn test_validate_chain() {
+        let valid = Identifier::from_v2("chr1:100-200_+:10-50_-:1-5_+").unwrap();
+        assert_eq!(valid.validate_chain(), Ok(()));
+        assert_eq!(Identifier::from_v2("chr1").unwrap().validate_chain(), Ok(()));
+
+        let test_cases = vec![
+            ("chr1:100-200_+:10-102_-", SmittenError::ChainOutOfBounds { level: 1, start: 10, end: 102, parent_len: 101 }),
+            ("chr1:100-200_+:10-50_-:1-42_+", SmittenError::ChainOutOfBounds { level: 2, start: 1, end: 42, parent_len: 41 }),
+            ("chr1:0-200_+", SmittenError::ZeroCoordinate { start: 0, end: 200 }),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.validate_chain(), Err(expected), "Chain error mismatch for ID: {}", id);
+        }
+
+        // Decreasing ranges can't come out of from_v2, so edit one in by hand.
+        let mut edited = valid.clone();
+        edited.ranges[1] = Range { start: 50, end: 10, orientation: Strand::Reverse };
+        assert_eq!(edited.validate_chain(), Err(SmittenError::DecreasingRange { start: 50, end: 10 }));
+    }
+
+    #[test]
+    fn test_from_bed() {
+        let test_cases = vec![
+            ("chr1", 99, 200, Strand::Forward, "chr1:100-200_+"),
+            ("chr1", 0, 1, Strand::Reverse, "chr1:1-1_-"),
+            ("chrUn_gl000220", 1000, 2000, Strand::Forward, "chrUn_gl000220:1001-2000_+"),
+        ];
+
+        for (sequence_id, start0, end, strand, expected) in test_cases {
+            let parsed_id = Identifier::from_bed(sequence_id, start0, end, strand).unwrap();
+            assert_eq!(parsed_id.to_string(), expected);
+            assert_eq!(parsed_id.to_bed_coords(), Ok(vec![(start0, end, strand)]));
+        }
+
+        assert_eq!(Identifier::from_bed("chr1", 100, 100, Strand::Forward), Err(SmittenError::DecreasingRange { start: 101, end: 100 }));
+        assert_eq!(Identifier::from_bed("", 0, 10, Strand::Forward), Err(SmittenError::EmptySequenceId(String::new())));
+        assert!(matches!(Identifier::from_bed("chr1", usize::MAX, usize::MAX, Strand::Forward), Err(SmittenError::CoordinateOverflow(_))));
+    }
+
+    #[test]
+    fn test_from_v2_with_separator() {
+        let test_cases = vec![
+            ("chr1|100-200_+", '|', "chr1:100-200_+"),
+            ("hg38:chr1|100-200_+|10-50_-", '|', "hg38:chr1:100-200_+:10-50_-"),
+            ("chr1/100-200", '/', "chr1:100-200_+"),
+            ("chr.1.100-200_-", '.', "chr.1:100-200_-"),
+            ("hg38:chr1:100-200_+", ':', "hg38:chr1:100-200_+"),
+        ];
+
+        for (id, sep, expected) in test_cases {
+            let parsed_id = Identifier::from_v2_with_separator(id, sep).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "V2 format mismatch for ID: {}", id);
+        }
+
+        // Without a matching separator the ranges stay part of the sequence name
+        assert_eq!(Identifier::from_v2_with_separator("chr1:100-200_+", '|').unwrap().sequence_id, "100-200_+");
+        assert!(matches!(
+            Identifier::from_v2_with_separator("chr1|200-100_+", '|'),
+            Err(SmittenError::DecreasingRange { start: 200, end: 100 })
+        ));
+
+        for sep in ['1', '-', '+', '_', ' '] {
+            assert_eq!(Identifier::from_v2_with_separator("chr1", sep), Err(SmittenError::InvalidSeparator(sep)));
+        }
+    }
+
+    #[test]
+    fn test_mirror() {
+        let test_cases = vec![
+            ("chr1:10-20_+", 100, "chr1:81-91_-"),
+            ("chr1:81-91_-", 100, "chr1:10-20_+"),
+            ("chr1:1-100_+", 100, "chr1:1-100_-"),
+            ("hg38:chr1:100-200_+:1-10_+", 101, "hg38:chr1:100-200_+:92-101_-"),
+        ];
+
+        for (id, seq_len, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            let mirrored_id = parsed_id.mirror(seq_len).unwrap();
+            assert_eq!(mirrored_id.to_string(), expected, "Mirror mismatch for ID: {}", id);
+            assert_eq!(mirrored_id.mirror(seq_len).unwrap(), parsed_id);
+        }
+
+        assert_eq!(
+            Identifier::from_v2("chr1:10-20_+").unwrap().mirror(15),
+            Err(SmittenError::SubrangeOutOfBounds { start: 10, end: 20, parent_len: 15 })
+        );
+        assert!(matches!(Identifier::from_v2("chr1").unwrap().mirror(100), Err(SmittenError::WholeSequence(_))));
+    }
+
+    #[test]
+    fn test_from_unknown_format_verbose() {
+        let test_cases = vec![
+            ("chr1_1_5_3:1-3_+", "chr1_1_5_3:1-3_+", IDVersion::V2, vec!["'_5_3'", "V0 format differs from the inferred V2"]),
+            ("chrUn_gl000220_1_100", "chrUn_gl000220:1-100_+", IDVersion::V0, vec!["'chrUn_gl000220' contains '_'"]),
+            ("chr1:200_100", "chr1:200_100", IDVersion::Undefined, vec!["not a recognized range"]),
+        ];
+
+        for (id, expected, expected_version, fragments) in test_cases {
+            let (parsed_id, version, notes) = Identifier::from_unknown_format_verbose(id, false).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "V2 format mismatch for ID: {}", id);
+            assert_eq!(version, expected_version, "IDVersion mismatch for ID: {}", id);
+            assert_eq!(notes.len(), 1, "Unexpected notes for ID: {}: {:?}", id, notes);
+            for fragment in fragments {
+                assert!(notes[0].contains(fragment), "Note {:?} for ID {} is missing {:?}", notes[0], id, fragment);
+            }
+        }
+
+        for id in ["chr1_100_200", "hg38:chr1:100-200_+:10-50_-", "chr1"] {
+            let (_, _, notes) = Identifier::from_unknown_format_verbose(id, false).unwrap();
+            assert!(notes.is_empty(), "Unexpected notes for ID: {}: {:?}", id, notes);
+        }
+        assert!(Identifier::from_unknown_format_verbose("chr2_200_100", false).is_err());
+    }
+
+    #[test]
+    fn test_depth() {
+        let test_cases = vec![
+            ("chr1", 0),
+            ("hg38:chr1", 0),
+            ("chr1:100-200_+", 1),
+            ("chr1:100-200_+:1-5_+", 2),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", 3),
+        ];
+
+        for (id, expected) in test_cases {
+            assert_eq!(Identifier::from_v2(id).unwrap().depth(), expected, "Depth mismatch for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_from_v2_with_whole_strand() {
+        let test_cases = vec![
+            ("chr1_-", None, "chr1", Some(Strand::Reverse)),
+            ("hg38:chr1_+", Some("hg38"), "chr1", Some(Strand::Forward)),
+            ("chr1", None, "chr1", None),
+            ("chrUn_gl000220", None, "chrUn_gl000220", None),
+            ("_-", None, "_-", None),
+        ];
+
+        for (id, assembly, sequence_id, strand) in test_cases {
+            let parsed_id = Identifier::from_v2_with_whole_strand(id).unwrap();
+            assert_eq!(parsed_id.assembly_id.as_deref(), assembly, "Assembly mismatch for ID: {}", id);
+            assert_eq!(parsed_id.sequence_id, sequence_id, "Sequence mismatch for ID: {}", id);
+            assert_eq!(parsed_id.whole_sequence_strand, strand, "Strand mismatch for ID: {}", id);
+            assert_eq!(parsed_id.to_string(), id, "Display mismatch for ID: {}", id);
+        }
+
+        let reverse = Identifier::from_v2_with_whole_strand("chr1_-").unwrap();
+        let unspecified = Identifier::from_v2_with_whole_strand("chr1").unwrap();
+        assert_ne!(reverse, unspecified);
+        assert!(unspecified < reverse);
+        assert_eq!(reverse.root(), unspecified);
+
+        // Ranged identifiers and plain from_v2 are unaffected
+        let ranged = Identifier::from_v2_with_whole_strand("chr1_-:100-200_+").unwrap();
+        assert_eq!((ranged.sequence_id.as_str(), ranged.whole_sequence_strand), ("chr1_-", None));
+        assert_eq!(Identifier::from_v2("chr1_-").unwrap().sequence_id, "chr1_-");
+
+        // The strand of the whole sequence no longer applies once it has ranges
+        let child = reverse.project_child(1, 5, Strand::Forward).unwrap();
+        let plain = Identifier::from_v2("chr1:1-5_+").unwrap();
+        assert_eq!(child.to_string(), "chr1:1-5_+");
+        assert_eq!(child, plain);
+        assert!(child.same_span(&plain).unwrap());
+        assert_eq!(dedup_by_span(vec![child, plain.clone()]).unwrap(), vec![plain.clone()]);
+
+        let mut edited = reverse.clone();
+        edited.ranges.push(Range { start: 1, end: 5, orientation: Strand::Forward });
+        assert_eq!(edited, plain);
+        assert_eq!(Identifier::from_v2(&edited.to_string()).unwrap(), edited);
+        let hash = |id: &Identifier| {
+            use core::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            id.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&edited), hash(&plain));
+    }
+
+    #[test]
+    fn test_guess_coord_system() {
+        let test_cases = vec![
+            (vec!["chr1_0_100", "chr2_100_200"], Some(CoordSystem::ZeroBasedHalfOpen)),
+            (vec!["chr1:100-0", "chr1:1-10"], Some(CoordSystem::ZeroBasedHalfOpen)),
+            (vec!["chr1:100-200_+:0-10_-"], Some(CoordSystem::ZeroBasedHalfOpen)),
+            (vec!["chr1:100-100_+", "chr2:1-10_+"], Some(CoordSystem::OneBasedFullyClosed)),
+            (vec!["chr1_5_5_R"], Some(CoordSystem::OneBasedFullyClosed)),
+            (vec!["chr1:100-200_+", "chr2_1_10"], None),
+            (vec!["chr1:0-10_+", "chr2:5-5_+"], None),
+            (vec!["chr1", "chr1:200_0"], None),
+            (vec![], None),
+        ];
+
+        for (ids, expected) in test_cases {
+            assert_eq!(guess_coord_system(&ids), expected, "Coordinate system mismatch for IDs: {:?}", ids);
+        }
+
+        // A zero coordinate beyond MAX_RANGE_DEPTH ranges is not looked at
+        let within_id = format!("chr1:0-10_+{}", ":1-2_+".repeat(MAX_RANGE_DEPTH - 1));
+        assert_eq!(guess_coord_system(&[within_id.as_str()]), Some(CoordSystem::ZeroBasedHalfOpen));
+        let deep_id = format!("chr1:0-10_+{}", ":1-2_+".repeat(MAX_RANGE_DEPTH));
+        assert_eq!(guess_coord_system(&[deep_id.as_str()]), None);
+    }
+
+    #[test]
+    fn test_as_interval() {
+        let test_cases = vec![
+            ("chr1:100-200_+", Some(99..200)),
+            ("chr1:100-200_-", Some(99..200)),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", Some(144..149)),
+            ("chr1:1-1_+", Some(0..1)),
+            ("chr1", None),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.as_interval(), Ok(expected), "Interval mismatch for ID: {}", id);
+        }
+
+        assert!(Identifier::from_v2("chr1:100-200_+:150-300_+").unwrap().as_interval().is_err());
+        assert_eq!(
+            Identifier::from_v2("chr1:0-10_+").unwrap().as_interval(),
+            Err(SmittenError::ZeroCoordinate { start: 0, end: 10 })
+        );
+    }
+
+    #[test]
+    fn test_normalize_strand_patterns() {
+        // Each level fits within the one before it: lengths 100, 50, 20, 7
+        let levels = [(101, 200), (11, 60), (6, 25), (3, 9)];
+
+        for depth in 2..=4 {
+            for pattern in 0..(1u32 << depth) {
+                let strands: Vec<Strand> = (0..depth)
+                    .map(|level| if pattern & (1 << level) != 0 { Strand::Reverse } else { Strand::Forward })
+                    .collect();
+                let ranges: Vec<Range> = levels[..depth]
+                    .iter()
+                    .zip(&strands)
+                    .map(|(&(start, end), &orientation)| Range { start, end, orientation })
+                    .collect();
+
+                // Map both ends of the innermost range up through every parent frame
+                let (mut low, mut high) = (ranges[depth - 1].start, ranges[depth - 1].end);
+                for parent in ranges[..depth - 1].iter().rev() {
+                    let place = |pos: usize| match parent.orientation {
+                        Strand::Forward => parent.start + pos - 1,
+                        Strand::Reverse => parent.end - pos + 1,
+                    };
+                    (low, high) = (place(low).min(place(high)), place(low).max(place(high)));
+                }
+                let reverse_count = strands.iter().filter(|&&strand| strand == Strand::Reverse).count();
+                let orientation = if reverse_count % 2 == 1 { Strand::Reverse } else { Strand::Forward };
+
+                let id = Identifier::new(None, "chr1".to_string(), ranges).unwrap();
+                let expected = Identifier::new(None, "chr1".to_string(), vec![Range { start: low, end: high, orientation }]).unwrap();
+                assert_eq!(id.normalize(), Ok(expected), "Normalization mismatch for ID: {}", id);
+            }
+        }
+
+        // Hand-computed chains, including alternating strands
+        let test_cases = vec![
+            ("chr1:101-200_-:11-60_+", "chr1:141-190_-"),
+            ("chr1:101-200_+:11-60_-:6-25_+:3-9_-", "chr1:147-153_+"),
+            ("chr1:101-200_-:11-60_+:6-25_-:3-9_+", "chr1:168-174_+"),
+            ("chr1:101-200_-:11-60_-:6-25_-:3-9_-", "chr1:157-163_+"),
+        ];
+        for (id, expected) in test_cases {
+            assert_eq!(Identifier::from_v2(id).unwrap().normalize().unwrap().to_string(), expected, "Normalization mismatch for ID: {}", id);
+        }
+    }
+
+    #[test]
+    fn test_strand_compose() {
+        let test_cases = vec![
+            (Strand::Forward, Strand::Forward, Strand::Forward),
+            (Strand::Forward, Strand::Reverse, Strand::Reverse),
+            (Strand::Reverse, Strand::Forward, Strand::Reverse),
+            (Strand::Reverse, Strand::Reverse, Strand::Forward),
+        ];
+
+        for (parent, child, expected) in test_cases {
+            assert_eq!(parent.compose(child), expected, "Composition mismatch for ({}, {})", parent, child);
+
+            // The same composition applies at every level of a chain
+            for prefix in ["chr1", "chr1:1-1000_+", "chr1:1-1000_-", "chr1:1-1000_+:1-500_-"] {
+                let id = Identifier::from_v2(&format!("{}:101-200_{}:11-60_{}", prefix, parent, child)).unwrap();
+                let outer = Identifier::from_v2(prefix).unwrap().absolute_range().unwrap().map_or(Strand::Forward, |span| span.2);
+                let (_, _, orientation) = id.absolute_range().unwrap().unwrap();
+                assert_eq!(orientation, outer.compose(expected), "Orientation mismatch for ID: {}", id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap();
+        assert_eq!(
+            parsed_id.explain(),
+            "assembly: hg38\n\
+             sequence: chr1\n\
+             level 1: 100-200 (+)\n\
+             level 2: 10-50 (-)\n\
+             level 3: 1-5 (+)\n\
+             normalized: hg38:chr1:145-149_-"
+        );
+
+        assert_eq!(Identifier::from_v2("chr1").unwrap().explain(), "sequence: chr1\nnormalized: chr1");
+        assert!(Identifier::from_v2("chr1:100-200_+:150-300_+")
+            .unwrap()
+            .explain()
+            .ends_with("normalized: error: Sequence sub-range 150-300 is outside the bounds of the parent range length 101."));
+    }
+
+    #[test]
+    fn test_strict_version_constructors() {
+        let test_cases = vec![
+            ("chr1_100_200_R", IDVersion::V0, "chr1:100-200_-"),
+            ("hg38:chr1_100_200_10_50", IDVersion::V0, "hg38:chr1:100-200_+:10-50_+"),
+            ("chr1:200-100", IDVersion::V1, "chr1:100-200_-"),
+            ("hg38:chr1:100-200:50-10", IDVersion::V1, "hg38:chr1:100-200_+:10-50_-"),
+            ("chr1:100-200_+", IDVersion::V2, "chr1:100-200_+"),
+        ];
+
+        for (id, version, expected) in test_cases {
+            for (constructor, constructor_version) in [
+                (Identifier::from_v0 as fn(&str) -> Result<Identifier, SmittenError>, IDVersion::V0),
+                (Identifier::from_v1, IDVersion::V1),
+            ] {
+                let result = constructor(id);
+                if version == constructor_version {
+                    assert_eq!(result.unwrap().to_string(), expected, "V2 format mismatch for ID: {}", id);
+                } else {
+                    assert_eq!(
+                        result,
+                        Err(SmittenError::VersionMismatch { id: id.to_string(), expected: constructor_version.clone(), found: version.clone() }),
+                        "Expected a version mismatch for ID: {}",
+                        id
+                    );
+                }
+            }
+        }
+
+        assert_eq!(Identifier::from_v0("hg38:chr1").unwrap().to_string(), "hg38:chr1");
+        assert_eq!(Identifier::from_v1("hg38:chr1").unwrap().to_string(), "hg38:chr1");
+        assert!(matches!(Identifier::from_v1("chr1:200_100"), Err(SmittenError::VersionMismatch { found: IDVersion::Undefined, .. })));
+        assert!(matches!(Identifier::from_v0("chr2_200_100"), Err(SmittenError::DecreasingRange { .. })));
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-").unwrap();
+        let ancestors: Vec<String> = parsed_id.ancestors().iter().map(|id| id.to_string()).collect();
+        assert_eq!(ancestors, vec!["hg38:chr1", "hg38:chr1:100-200_+", "hg38:chr1:100-200_+:10-50_-"]);
+        assert_eq!(parsed_id.ancestors().last(), Some(&parsed_id));
+
+        for ancestor in parsed_id.ancestors() {
+            assert_eq!(Identifier::from_v2(&ancestor.to_string()), Ok(ancestor));
         }
 
-        Ok(ret_str)
+        let whole = Identifier::from_v2("chr1").unwrap();
+        assert_eq!(whole.ancestors(), vec![whole]);
     }
-}
 
+    #[test]
+    fn test_truncate_depth() {
+        let parsed_id = Identifier::from_v2("hg38:chr1:100-900_+:10-500_-:20-200_+:5-50_-").unwrap();
+        let test_cases = vec![
+            (0, "hg38:chr1"),
+            (2, "hg38:chr1:100-900_+:10-500_-"),
+            (4, "hg38:chr1:100-900_+:10-500_-:20-200_+:5-50_-"),
+            (10, "hg38:chr1:100-900_+:10-500_-:20-200_+:5-50_-"),
+        ];
 
+        for (max_depth, expected) in test_cases {
+            let truncated = parsed_id.truncate_depth(max_depth);
+            assert_eq!(truncated.to_string(), expected, "Truncation mismatch for depth: {}", max_depth);
+            assert_eq!(truncated.assembly_id, parsed_id.assembly_id);
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_parse_with_trailing() {
+        let test_cases = vec![
+            ("chr1:100-200_+ gene=FOXP2", "chr1:100-200_+", Some("gene=FOXP2")),
+            ("chr1:100-200_+\tgene=FOXP2\tscore=7", "chr1:100-200_+", Some("gene=FOXP2\tscore=7")),
+            ("chr1_100_200_R  two spaces ", "chr1:100-200_-", Some(" two spaces ")),
+            ("hg38:chr1:100-200", "hg38:chr1:100-200_+", None),
+        ];
 
-    fn run_convert_id_test(
-        id: &str,
-        coord_type: &str,
-        exp_outcome: &str,
-        exp_version: Option<IDVersion>,
-        exp_v2_format: &str,
-    ) {
-        let zbho = coord_type == "zbho";
-        let result = Identifier::from_unknown_format(id, zbho, false);
+        for (input, expected, trailing) in test_cases {
+            let (parsed_id, parsed_trailing) = Identifier::parse_with_trailing(input, false).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "Identifier mismatch for input: {:?}", input);
+            assert_eq!(parsed_trailing.as_deref(), trailing, "Trailing text mismatch for input: {:?}", input);
+        }
 
-        match (result.clone(), exp_outcome, exp_version.clone()) {
-            // Expected pass case
-            (Ok((converted_id, version)), "pass", Some(expected_version)) => {
-                assert_eq!(version, expected_version, "IDVersion mismatch for ID: {}", id);
-                assert_eq!(converted_id.to_string(), exp_v2_format, "V2 format mismatch for ID: {}", id);
-            }
-            // Expected failure with None as version
-            (Err(err), "fail", None) => {
-                println!("Expected failure for ID: {}. Error message: {}", id, err);
-            }
-            // Expected failure with Some(Undefined) as version
-            (Err(err), "fail", Some(IDVersion::Undefined)) => {
-                println!("Expected specific failure for ID: {}. Error message: {}", id, err);
+        assert_eq!(Identifier::parse_with_trailing("chr1_100_200 x", true).unwrap().0.to_string(), "chr1:101-200_+");
+        assert!(Identifier::parse_with_trailing(" chr1:100-200_+", false).is_err());
+        assert!(Identifier::parse_with_trailing("chr2_200_100\tx", false).is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_check() {
+        for (id, coord_type, exp_outcome, _, _) in convert_id_test_cases() {
+            let result = Identifier::roundtrip_check(id, coord_type == "zbho");
+            if exp_outcome == "pass" {
+                assert_eq!(result, Ok(true), "Round-trip mismatch for ID: {}", id);
+            } else {
+                assert!(result.is_err(), "Expected failure for ID: {}", id);
             }
-            // Unexpected success when failure was expected
-            (Ok((converted_id, version)), "fail", _) => {
-                panic!(
-                    "Unexpected success for ID: {}. Expected outcome: fail, but got success with V2 ID: '{}' and version: {:?}",
-                    id, converted_id, version
-                );
+        }
+    }
+
+    #[test]
+    fn test_normalized_or_self() {
+        let test_cases = vec![
+            ("chr1_100_200", "chr1:100-200_+"),
+            ("hg38:chr1:200-100", "hg38:chr1:100-200_-"),
+            ("chr1", "chr1"),
+            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "hg38:chr1:145-149_-"),
+        ];
+
+        for (id, expected) in test_cases {
+            let (parsed_id, _) = Identifier::from_unknown_format(id, false, false).unwrap();
+            let result = parsed_id.normalized_or_self().unwrap();
+            assert_eq!(result.to_string(), expected, "Normalization mismatch for ID: {}", id);
+            if parsed_id.ranges.len() <= 1 {
+                assert_eq!(format!("{:?}", result), format!("{:?}", parsed_id), "Expected an unchanged clone for ID: {}", id);
+            } else {
+                assert_eq!(result, parsed_id.normalize().unwrap());
             }
-            // Unexpected failure when pass was expected
-            (Err(err), "pass", Some(expected_version)) => {
-                panic!(
-                    "Unexpected failure for ID: {}. Expected outcome: pass, version: {:?}. Error: {}",
-                    id, expected_version, err
-                );
+        }
+
+        let overflow = Identifier::from_v2(&format!("chr1:0-{}_+:1-5_+", usize::MAX)).unwrap();
+        assert!(matches!(overflow.normalized_or_self(), Err(SmittenError::CoordinateOverflow(_))));
+    }
+
+    #[test]
+    fn test_positions() {
+        let test_cases = vec![
+            ("chr1:100-200_+", 100, 200, 101),
+            ("chr1:100-200_-", 200, 100, 101),
+            ("chr1:100-200_+:10-50_-:1-5_+", 149, 145, 5),
+            ("chr1:7-7_-", 7, 7, 1),
+        ];
+
+        for (id, first, last, count) in test_cases {
+            let positions: Vec<usize> = Identifier::from_v2(id).unwrap().positions().unwrap().collect();
+            assert_eq!(positions.first(), Some(&first), "First position mismatch for ID: {}", id);
+            assert_eq!(positions.last(), Some(&last), "Last position mismatch for ID: {}", id);
+            assert_eq!(positions.len(), count, "Position count mismatch for ID: {}", id);
+            let expected: Vec<usize> = if first <= last { (first..=last).collect() } else { (last..=first).rev().collect() };
+            assert_eq!(positions, expected, "Positions are not consecutive for ID: {}", id);
+        }
+
+        let large = Identifier::from_v2(&format!("chr1:1-{}_-", usize::MAX)).unwrap();
+        assert_eq!(large.positions().unwrap().take(2).collect::<Vec<_>>(), vec![usize::MAX, usize::MAX - 1]);
+        assert!(matches!(Identifier::from_v2("chr1").unwrap().positions(), Err(SmittenError::WholeSequence(_))));
+    }
+
+    #[test]
+    fn test_normalized_strand() {
+        let test_cases = vec![
+            ("chr1:100-200_+", Some(Strand::Forward)),
+            ("chr1:100-200_-", Some(Strand::Reverse)),
+            ("chr1:100-200_+:10-50_-:1-5_+", Some(Strand::Reverse)),
+            ("chr1:100-200_-:10-50_-:1-5_+", Some(Strand::Forward)),
+            ("chr1:100-200_-:10-50_-:1-5_-", Some(Strand::Reverse)),
+            ("chr1", None),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2(id).unwrap();
+            assert_eq!(parsed_id.normalized_strand(), Ok(expected), "Strand mismatch for ID: {}", id);
+            if let Some(strand) = expected {
+                assert_eq!(parsed_id.normalize().unwrap().ranges[0].orientation, strand);
             }
-            // Catch-all for any unexpected outcome
-            _ => panic!(
-                "Unexpected outcome for ID: {}. Expected outcome: {}, version: {:?}, but got result: {:?}",
-                id, exp_outcome, exp_version, result
-            ),
         }
+
+        let whole = Identifier::from_v2_with_whole_strand("chr1_-").unwrap();
+        assert_eq!(whole.normalized_strand(), Ok(Some(Strand::Reverse)));
+        let overflow = Identifier::from_v2(&format!("chr1:0-{}_+:1-5_+", usize::MAX)).unwrap();
+        assert!(matches!(overflow.normalized_strand(), Err(SmittenError::CoordinateOverflow(_))));
     }
 
     #[test]
-    fn test_convert_id() {
+    fn test_relative_to_level() {
+        let parsed_id = Identifier::from_v2("chr1:100-200_+:10-50_-:1-5_+").unwrap();
         let test_cases = vec![
-            // V0 Examples
-            ("chr1_100_200", "obfc", "pass", Some(IDVersion::V0), "chr1:100-200_+"),
-            ("seq_100_200_R", "obfc", "pass", Some(IDVersion::V0), "seq:100-200_-"),
-            ("(ACC)n#Simple_1_10", "obfc", "pass", Some(IDVersion::V0), "(ACC)n#Simple:1-10_+"),
-            ("chr_1_100_200_10_20_R", "obfc", "pass", Some(IDVersion::V0), "chr_1:100-200_+:10-20_-"),
-            ("AMM1015_1000_2000_R_1_10_R", "obfc", "pass", Some(IDVersion::V0), "AMM1015:1000-2000_-:1-10_-"),
-            ("hg38:chr1_100_200", "obfc", "pass", Some(IDVersion::V0), "hg38:chr1:100-200_+"),
-            ("hg38:chr1_100_200_R", "obfc", "pass", Some(IDVersion::V0), "hg38:chr1:100-200_-"),
-            ("1_2000_3000_400_500_60_70_8_9", "obfc", "pass", Some(IDVersion::V0), "1:2000-3000_+:400-500_+:60-70_+:8-9_+"),
-            ("chr;1_100_200", "obfc", "pass", Some(IDVersion::V0), "chr;1:100-200_+"),
-            // Indistinguishable from a badly formatted V0 (with sequence positions in the wrong
-            // order)
-            ("NT_004321_0", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("_100_200", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("AMM::1002:Seq1:100_200", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            (":_100_200", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("chr_1_100_200_150_200_R", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("HSPA2_0_0", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("chr2_200_100", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            // V1 Examples
-            ("chr1:1-200", "obfc", "pass", Some(IDVersion::V1), "chr1:1-200_+"),
-            ("chr1:200-1", "obfc", "pass", Some(IDVersion::V1), "chr1:1-200_-"),
-            ("GCA1:chr2:540133-28232", "obfc", "pass", Some(IDVersion::V1), "GCA1:chr2:28232-540133_-"),
-            ("chr1:200-1:10-5", "obfc", "pass", Some(IDVersion::V1), "chr1:1-200_-:5-10_-"),
-            ("(ACC)n#Simple:400-500:20-1", "obfc", "pass", Some(IDVersion::V1), "(ACC)n#Simple:400-500_+:1-20_-"),
-            ("seq1@*@)(_:1-200", "obfc", "pass", Some(IDVersion::V1), "seq1@*@)(_:1-200_+"),
-            ("1:2000-3000:400-500:60-70:8-9", "obfc", "pass", Some(IDVersion::V1), "1:2000-3000_+:400-500_+:60-70_+:8-9_+"),
-            ("PB2_4_8_8:2709-6535", "obfc", "pass", Some(IDVersion::V1), "PB2_4_8_8:2709-6535_+"),
-            ("chromosome___:0-10", "zbho", "pass", Some(IDVersion::V1), "chromosome___:1-10_+"),
-            ("chr_1:100-200:200-150", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("ASeq:0-0", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("chr1:200-1:10-5_+", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("chr1:200-1_+", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("hg38:chr1:200-100_-", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("AMM::1002:Seq1:100-200", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("::100-200", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            // V2 Examples
-            ("chr1:100-200_+", "obfc", "pass", Some(IDVersion::V2), "chr1:100-200_+"),
-            ("chr1_1_5_3:1-3_+", "obfc", "pass", Some(IDVersion::V2), "chr1_1_5_3:1-3_+"),
-            ("chr1:100-200_+", "obfc", "pass", Some(IDVersion::V2), "chr1:100-200_+"),
-            ("hg38:chr1:100-200_+", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:100-200_+"),
-            ("hg38:chr1:100-200_-", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:100-200_-"),
-            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:100-200_+:10-50_-:1-5_+"),
-            ("hg38:chr1:200-200_-", "obfc", "pass", Some(IDVersion::V2), "hg38:chr1:200-200_-"),
-            ("hg38:chr1:33438223-33439283_+", "zbho", "pass", Some(IDVersion::V2), "hg38:chr1:33438224-33439283_+"),
-            ("JANCRE010000006.1:5563658-5564462_-:1-458_+", "obfc", "pass", Some(IDVersion::V2), "JANCRE010000006.1:5563658-5564462_-:1-458_+"),
-            ("JANCRE010000006.1_5563658_5564462_R:1-458_+", "obfc", "pass", Some(IDVersion::V2), "JANCRE010000006.1_5563658_5564462_R:1-458_+"),
-            ("hg_38:chr+1:10-40_+", "obfc", "pass", Some(IDVersion::V2), "hg_38:chr+1:10-40_+"),
-            ("hg-38:chr_1:10-40_+", "obfc", "pass", Some(IDVersion::V2), "hg-38:chr_1:10-40_+"),
-            //
-            // Special case a variant of Arians output for a time
-            ("chr13:51174547-51174560_R", "obfc", "pass", Some(IDVersion::V0), "chr13:51174547-51174560_-"),
-            // Unrecognizable Examples
-            //   Unrecognized ranges, returned as monolithic identifier in Undefined format
-            ("chr1:100_200_R", "obfc", "pass", Some(IDVersion::Undefined), "chr1:100_200_R"),
-            ("chr1:200_100", "obfc", "pass", Some(IDVersion::Undefined), "chr1:200_100"),
-            ("chr1", "obfc", "pass", Some(IDVersion::Undefined), "chr1"),
-            ("hg38:chr1", "obfc", "pass", Some(IDVersion::Undefined), "hg38:chr1"),
-            ("100_200", "obfc", "pass", Some(IDVersion::Undefined), "100_200"),
-            ("100-200", "obfc", "pass", Some(IDVersion::Undefined), "100-200"),
-            ("100:200", "obfc", "pass", Some(IDVersion::Undefined), "100:200"),
-            ("100:200:", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("100:200:seq:", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            //   Failures due to invalid characters
-            ("seq1_ 1_2", "obfc", "fail", Some(IDVersion::Undefined), ""),
-            ("seq1:1-2\n_+", "obfc", "fail", Some(IDVersion::Undefined), ""),
+            (0, (145, 149, Strand::Reverse)),
+            (1, (46, 50, Strand::Reverse)),
+            (2, (1, 5, Strand::Forward)),
         ];
 
-        for (id, coord_type, exp_outcome, exp_version, exp_v2_format) in test_cases {
-            run_convert_id_test(id, coord_type, exp_outcome, exp_version, exp_v2_format);
+        for (level, expected) in test_cases {
+            assert_eq!(parsed_id.relative_to_level(level), Ok(expected), "Span mismatch for level: {}", level);
         }
+        assert_eq!(parsed_id.relative_to_level(0).map(Some), parsed_id.absolute_range());
+
+        assert_eq!(
+            parsed_id.relative_to_level(3),
+            Err(SmittenError::RangeIndexOutOfBounds { index: 3, depth: 3 })
+        );
+        assert!(matches!(Identifier::from_v2("chr1").unwrap().relative_to_level(0), Err(SmittenError::WholeSequence(_))));
     }
 
-fn run_parse_id_test(
-    id: &str,
-    expected_outcome: &str,
-    expected_assembly: &str,
-    expected_sequence_id: &str,
-    expected_ranges: Vec<(usize, usize, char)>,
-    expected_version: Option<IDVersion>,
-    expected_normalization: &str,
-) {
-    let result = Identifier::from_v2(id);
+    #[test]
+    fn test_normalize_many() {
+        let ids = vec![
+            Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap(),
+            Identifier::from_v2(&format!("chr1:0-{}_+:1-5_+", usize::MAX)).unwrap(),
+            Identifier::from_v2("chr1").unwrap(),
+            Identifier::from_v2("chr1:100-200_+:150-300_+").unwrap(),
+            Identifier::from_v2("chr2:100-200_-:1-10_+").unwrap(),
+        ];
 
-    match (result.as_ref(), expected_outcome, expected_version.clone()) {
-        // Expected pass case
-        (Ok(parsed_id), "pass", Some(expected_version)) => {
-            println!("Parsed ID: {:?}", parsed_id); // Debug output for parsed ID
-            assert_eq!(
-                parsed_id.assembly_id.as_deref().unwrap_or(""),
-                expected_assembly,
-                "Assembly mismatch for ID: {}", id
-            );
-            assert_eq!(parsed_id.sequence_id, expected_sequence_id, "Sequence ID mismatch for ID: {}", id);
-            assert_eq!(parsed_id.inferred_version, expected_version, "IDVersion mismatch for ID: {}", id);
+        let results = Identifier::normalize_many(ids);
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap().to_string(), "hg38:chr1:145-149_-");
+        assert!(matches!(results[1], Err(SmittenError::CoordinateOverflow(_))));
+        assert_eq!(results[2].as_ref().unwrap().to_string(), "chr1");
+        assert_eq!(results[3], Err(SmittenError::SubrangeOutOfBounds { start: 150, end: 300, parent_len: 101 }));
+        assert_eq!(results[4].as_ref().unwrap().to_string(), "chr2:191-200_-");
 
-            let parsed_ranges: Vec<(usize, usize, char)> = parsed_id
-                .ranges
-                .iter()
-                .map(|r| (r.start, r.end, r.orientation))
-                .collect();
-            assert_eq!(parsed_ranges, expected_ranges, "Range mismatch for ID: {}", id);
+        assert!(Identifier::normalize_many(Vec::new()).is_empty());
+    }
 
-            // Normalization check if expected_normalization is provided
-            if !expected_normalization.is_empty() {
-                match parsed_id.normalize() {
-                    Ok(normalized_id) => {
-                        assert_eq!(normalized_id.to_string(), expected_normalization, "Normalization mismatch for ID: {}", id);
-                    }
-                    Err(err) => {
-                        panic!("Normalization failed for ID: {}. Error: {}", id, err);
-                    }
-                }
-            }
+    #[test]
+    fn test_set_range() {
+        let mut parsed_id = Identifier::from_v2("hg38:chr1:100-200_+:10-50_-:1-5_+").unwrap();
+        parsed_id.set_range(1, 20, 60, Strand::Forward).unwrap();
+        assert_eq!(parsed_id.to_string(), "hg38:chr1:100-200_+:20-60_+:1-5_+");
+        parsed_id.set_range(0, 1000, 1100, Strand::Reverse).unwrap();
+        parsed_id.set_range(2, 41, 41, Strand::Reverse).unwrap();
+        assert_eq!(parsed_id.to_string(), "hg38:chr1:1000-1100_-:20-60_+:41-41_-");
+
+        let test_cases = vec![
+            (3, 1, 5, SmittenError::RangeIndexOutOfBounds { index: 3, depth: 3 }),
+            (1, 60, 20, SmittenError::DecreasingRange { start: 60, end: 20 }),
+            (1, 0, 20, SmittenError::ZeroCoordinate { start: 0, end: 20 }),
+            (1, 90, 102, SmittenError::SubrangeOutOfBounds { start: 90, end: 102, parent_len: 101 }),
+            (1, 1, 30, SmittenError::SubrangeOutOfBounds { start: 41, end: 41, parent_len: 30 }),
+        ];
 
+        let original = Identifier::from_v2("hg38:chr1:100-200_+:20-60_+:41-41_-").unwrap();
+        for (index, start, end, expected) in test_cases {
+            let mut parsed_id = original.clone();
+            assert_eq!(parsed_id.set_range(index, start, end, Strand::Forward), Err(expected));
+            assert_eq!(parsed_id, original);
+        }
+
+        let mut full_width = Identifier::from_v2(&format!("chr1:0-{}_+:1-5_+", usize::MAX)).unwrap();
+        assert!(matches!(full_width.set_range(1, 1, 10, Strand::Forward), Err(SmittenError::CoordinateOverflow(_))));
+        assert_eq!(full_width.set_range(0, 0, 10, Strand::Forward), Err(SmittenError::ZeroCoordinate { start: 0, end: 10 }));
+    }
 
+    #[test]
+    fn test_pad() {
+        let test_cases = vec![
+            ("chr1:100-200_+", 10, 20, "chr1:90-220_+"),
+            ("chr1:100-200_-", 10, 20, "chr1:80-210_-"),
+            ("chr1:5-10_+", 10, 0, "chr1:1-10_+"),
+            ("chr1:5-10_-", 0, 10, "chr1:1-10_-"),
+            ("chr1:1-10_+", 0, 0, "chr1:1-10_+"),
+            ("chr1:100-200_+:10-50_-", 5, 1, "chr1:108-154_-"),
+        ];
 
+        for (id, upstream, downstream, expected) in test_cases {
+            let padded_id = Identifier::from_v2(id).unwrap().pad(upstream, downstream).unwrap();
+            assert_eq!(padded_id.to_string(), expected, "Padding mismatch for ID: {}", id);
         }
-        // Expected failure case
-        (Err(err), "fail", None) => {
-            println!("Expected failure for ID: {}. Error message: {}", id, err);
+
+        assert!(matches!(Identifier::from_v2("chr1").unwrap().pad(1, 1), Err(SmittenError::WholeSequence(_))));
+        assert!(matches!(
+            Identifier::from_v2("chr1:100-200_+").unwrap().pad(0, usize::MAX),
+            Err(SmittenError::CoordinateOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_degenerate() {
+        let full_width = format!("chr1:0-{}_+:1-5_+", usize::MAX);
+        let test_cases = vec![
+            ("chr1:100-200_+:1-101_+", true),
+            ("chr1:100-200_+:1-101_-", true),
+            ("chr1:100-200_+:10-50_-:1-41_+", true),
+            ("chr1:100-200_+:1-100_+", false),
+            ("chr1:100-200_+:2-101_+", false),
+            ("chr1:100-200_+:10-50_-:1-5_+", false),
+            ("chr1:100-200_+", false),
+            ("chr1", false),
+            (&full_width, false),
+        ];
+
+        for (id, expected) in test_cases {
+            assert_eq!(Identifier::from_v2(id).unwrap().is_degenerate(), expected, "Degeneracy mismatch for ID: {}", id);
         }
-        // Unexpected success when failure was expected
-        (Ok(parsed_id), "fail", _) => {
-            panic!(
-                "Unexpected success for ID: {}. Expected outcome: fail, but got success with Assembly: '{:?}', Sequence ID: '{}', Ranges: {:?}, IDVersion: {:?}",
-                id, parsed_id.assembly_id, parsed_id.sequence_id, parsed_id.ranges, parsed_id.inferred_version
-            );
+    }
+
+    #[test]
+    fn test_parse_id_ref() {
+        let deep_id = format!("chr1{}", ":1-1_+".repeat(MAX_RANGE_DEPTH + 1));
+        let edge_cases = [
+            "chr1:", ":chr1", "100:200:", ":chr1:100-200_+", "a:b:c:1-2_+", "chr1:1-2_+_-", "chr1:1-2-3_+",
+            "chr1:01-2", "chr1:-1-2", "chr1::1-2_+", "hg38:chr1:1-1", "chr1:1-99999999999999999999999", &deep_id,
+            "chr1:١-٥_+", "hg38:chr1:100-200_+:١-٥_-", "chr1:1-٥", "a:b:١-٥",
+        ];
+        let test_cases = convert_id_test_cases()
+            .into_iter()
+            .map(|(id, _, exp_outcome, _, _)| (id, exp_outcome))
+            .chain(edge_cases.iter().map(|id| (*id, "edge")));
+
+        for (id, exp_outcome) in test_cases {
+            let borrowed = Identifier::parse_id_ref(id);
+            match Identifier::from_v2(id) {
+                Ok(owned) => {
+                    let borrowed = borrowed.unwrap_or_else(|err| panic!("Unexpected failure for ID: {}: {}", id, err));
+                    assert_eq!(borrowed.to_owned(), owned, "Owned mismatch for ID: {}", id);
+                    assert_eq!(borrowed.depth(), owned.depth(), "Depth mismatch for ID: {}", id);
+                }
+                Err(err) => assert_eq!(borrowed, Err(err), "Error mismatch for ID: {} ({})", id, exp_outcome),
+            }
         }
-        // Unexpected failure when pass was expected
-        (Err(err), "pass", Some(expected_version)) => {
-            panic!(
-                "Unexpected failure for ID: {}. Expected outcome: pass, version: {:?}. Error: {}",
-                id, expected_version, err
-            );
+
+        let id = String::from("hg38:chr1:100-200_+:10-50_-:1-5");
+        let borrowed = Identifier::parse_id_ref(&id).unwrap();
+        let input = id.as_bytes().as_ptr_range();
+        assert!(input.contains(&borrowed.sequence_id.as_ptr()));
+        assert!(input.contains(&borrowed.assembly_id.unwrap().as_ptr()));
+        assert_eq!((borrowed.assembly_id, borrowed.sequence_id), (Some("hg38"), "chr1"));
+        let ranges: Vec<Range> = borrowed.ranges().collect();
+        assert_eq!(ranges, Identifier::from_v2(&id).unwrap().ranges);
+    }
+
+    #[test]
+    fn test_strand_histogram() {
+        let ids: Vec<Identifier> = [
+            "hg38:chr1:100-200_+",
+            "hg19:chr1:100-200_-",
+            "chr1:100-200_-:10-20_-",
+            "chr1:100-200_+:10-20_-:1-5_+",
+            "chr2",
+            "hg38:chr2",
+            "chr3:1-10_+",
+        ]
+        .iter()
+        .map(|id| Identifier::from_v2(id).unwrap())
+        .collect();
+
+        assert_eq!(strand_histogram(&ids), Ok((3, 2, 2)));
+        assert_eq!(strand_histogram(ids.iter().filter(|id| id.assembly_id.is_some())), Ok((1, 1, 1)));
+        assert_eq!(strand_histogram(&Vec::new()), Ok((0, 0, 0)));
+
+        let invalid = [Identifier::from_v2("chr1:100-200_+:150-300_+").unwrap()];
+        assert!(strand_histogram(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_from_v2_lenient_orientation_letters() {
+        let test_cases = vec![
+            ("chr1:100-200_R", "chr1:100-200_-"),
+            ("chr1:100-200_F", "chr1:100-200_+"),
+            ("hg38:chr1:100-200_F:10-50_R:1-5_+", "hg38:chr1:100-200_+:10-50_-:1-5_+"),
+            ("chr1:1,000-2,000_R", "chr1:1000-2000_-"),
+        ];
+
+        for (id, expected) in test_cases {
+            let parsed_id = Identifier::from_v2_lenient(id).unwrap();
+            assert_eq!(parsed_id.to_string(), expected, "V2 format mismatch for ID: {}", id);
         }
-        // Catch-all for any unexpected outcome
-        _ => panic!(
-            "Unexpected outcome for ID: {}. Expected outcome: {}, version: {:?}, but got result: {:?}",
-            id, expected_outcome, expected_version, result
-        ),
+        assert_eq!(Identifier::from_v2_lenient("chr1:100-200_R").unwrap().ranges[0].orientation, Strand::Reverse);
+
+        // The strict parser keeps F/R as part of the name
+        let strict = Identifier::from_v2("chr1:100-200_R").unwrap();
+        assert_eq!((strict.assembly_id.as_deref(), strict.sequence_id.as_str()), (Some("chr1"), "100-200_R"));
+        assert!(strict.is_whole_sequence());
     }
-}
+
     #[test]
-    fn test_parse_id() {
+    fn test_bounding() {
         let test_cases = vec![
-            ("chr1:100-200_+", "pass", "", "chr1", vec![(100, 200, '+')], Some(IDVersion::V2), "chr1:100-200_+"),
-            ("hg38:chr1:100-200_+", "pass", "hg38", "chr1", vec![(100, 200, '+')], Some(IDVersion::V2), "hg38:chr1:100-200_+"),
-            ("hg38:chr1:100-200_-", "pass", "hg38", "chr1", vec![(100, 200, '-')], Some(IDVersion::V2), "hg38:chr1:100-200_-"),
-            ("hg38:chr1:100-200_+:10-50_-:1-5_+", "pass", "hg38", "chr1", vec![(100, 200, '+'), (10, 50, '-'), (1, 5, '+')], Some(IDVersion::V2), "hg38:chr1:145-149_-"),
-            ("hg38:chr1:200-100_-", "fail", "", "", vec![], None, ""),
-            ("hg_38:chr+1:10-40_+", "pass", "hg_38", "chr+1", vec![(10, 40, '+')], Some(IDVersion::V2), "hg_38:chr+1:10-40_+"),
-            // Add remaining test cases here in the same format
+            (vec!["chr1:100-200_+", "chr1:150-250_-", "chr1:180-300_+"], "chr1:100-300_+"),
+            (vec!["hg38:chr1:500-600_-", "hg38:chr1:100-200_+", "hg38:chr1:1000-1100_-"], "hg38:chr1:100-1100_+"),
+            (vec!["chr1:100-200_+:10-50_-", "chr1:100-200_-"], "chr1:100-200_+"),
+            (vec!["chr1:100-200_-"], "chr1:100-200_+"),
         ];
 
-        for (id, outcome, assembly, seq_id, ranges, version, normalization) in test_cases {
-            run_parse_id_test(id, outcome, assembly, seq_id, ranges, version, normalization);
+        for (ids, expected) in test_cases {
+            let parsed: Vec<Identifier> = ids.iter().map(|id| Identifier::from_v2(id).unwrap()).collect();
+            assert_eq!(bounding(&parsed).unwrap().to_string(), expected, "Bounding mismatch for IDs: {:?}", ids);
         }
+
+        let mixed = [Identifier::from_v2("chr1:1-10_+").unwrap(), Identifier::from_v2("chr2:1-10_+").unwrap()];
+        assert!(matches!(bounding(&mixed), Err(SmittenError::RootMismatch { .. })));
+        let whole = [Identifier::from_v2("chr1:1-10_+").unwrap(), Identifier::from_v2("chr1").unwrap()];
+        assert!(matches!(bounding(&whole), Err(SmittenError::WholeSequence(_))));
+        assert_eq!(bounding(&[]), Err(SmittenError::NoIdentifiers));
+    }
+
+    #[test]
+    fn test_dedup_by_span() {
+        let ids: Vec<Identifier> = vec!["chr1:200-100", "chr2_10_20", "chr1:1-500_+:100-200_-", "chr1:100-200_+", "chr1:100-200_-", "chr2:10-20_+"]
+            .into_iter()
+            .map(|id| Identifier::from_unknown_format(id, false, true).unwrap().0)
+            .collect();
+
+        let unique: Vec<String> = dedup_by_span(ids).unwrap().iter().map(|id| id.to_string()).collect();
+        assert_eq!(unique, vec!["chr1:100-200_-", "chr2:10-20_+", "chr1:100-200_+"]);
+
+        let overflow = Identifier::from_v2(&format!("chr1:0-{}_+:1-5_+", usize::MAX)).unwrap();
+        assert!(matches!(dedup_by_span(vec![overflow]), Err(SmittenError::CoordinateOverflow(_))));
+        assert_eq!(dedup_by_span(Vec::new()), Ok(Vec::new()));
     }
 
+    #[test]
+    fn test_smitten_macro() {
+        assert_eq!(smitten!("hg38:chr1:100-200_+"), Identifier::from_v2("hg38:chr1:100-200_+").unwrap());
+        assert_eq!(smitten!("chr1:100-200:10-50_-").to_string(), "chr1:100-200_+:10-50_-");
+        assert_eq!(smitten!("chr1").ranges, Vec::new());
+        assert_eq!(smitten!("chr1_100_200").sequence_id, "chr1_100_200");
+        assert_eq!(smitten!("hg38:chr1:5-5_-").normalize().unwrap().to_string(), "hg38:chr1:5-5_-");
+    }
 
 }
 
@@ -0,0 +1,128 @@
+//! The `smitten!` macro for identifier literals checked at compile time.
+
+use crate::MAX_RANGE_DEPTH;
+
+/// Parses a V2 identifier literal, checking at compile time that `from_v2`
+/// accepts it so the expansion cannot fail at run time.
+///
+/// ```
+/// use smitten::{smitten, Identifier};
+///
+/// let id = smitten!("hg38:chr1:100-200_+:10-50_-");
+/// assert_eq!(id, Identifier::from_v2("hg38:chr1:100-200_+:10-50_-").unwrap());
+/// ```
+///
+/// An invalid literal, e.g. `smitten!("chr1:200-100_+")`, is a compile error
+/// naming the problem.  Literals are limited to ASCII.
+#[macro_export]
+macro_rules! smitten {
+    ($id:literal) => {{
+        const _: () = $crate::validate_v2_literal($id);
+        $crate::Identifier::from_v2($id).expect("smitten! literal was validated at compile time")
+    }};
+}
+
+/// Panics, failing const evaluation, unless `from_v2` accepts `id`.  The
+/// ranges are stripped from the end as in `split_last_range`.
+#[doc(hidden)]
+pub const fn validate_v2_literal(id: &str) {
+    let bytes = id.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\t' | b'\n' | 0x0B | 0x0C | b'\r' => panic!("smitten!: the identifier contains whitespace"),
+            0x80..=0xFF => panic!("smitten!: identifier literals must be ASCII"),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut end = bytes.len();
+    let mut depth = 0;
+    loop {
+        let mut range_end = end;
+        if range_end >= 2 && bytes[range_end - 2] == b'_' && (bytes[range_end - 1] == b'+' || bytes[range_end - 1] == b'-') {
+            range_end -= 2;
+        }
+        let end_digits = digits_before(bytes, range_end);
+        if end_digits == range_end || end_digits == 0 || bytes[end_digits - 1] != b'-' {
+            break;
+        }
+        let start_digits = digits_before(bytes, end_digits - 1);
+        if start_digits == end_digits - 1 || start_digits == 0 || bytes[start_digits - 1] != b':' {
+            break;
+        }
+
+        if parse_digits(bytes, start_digits, end_digits - 1) > parse_digits(bytes, end_digits, range_end) {
+            panic!("smitten!: a range start is greater than its end");
+        }
+        if depth >= MAX_RANGE_DEPTH {
+            panic!("smitten!: the identifier exceeds MAX_RANGE_DEPTH ranges");
+        }
+        depth += 1;
+        end = start_digits - 1;
+    }
+
+    let mut colons = 0;
+    let mut first_colon = 0;
+    i = 0;
+    while i < end {
+        if bytes[i] == b':' {
+            if colons == 0 {
+                first_colon = i;
+            }
+            colons += 1;
+        }
+        i += 1;
+    }
+    if colons > 1 {
+        panic!("smitten!: the identifier has more than one ':' before its ranges");
+    }
+    if colons == 1 && first_colon == 0 {
+        panic!("smitten!: the assembly identifier is empty");
+    }
+    if end == 0 || (colons == 1 && first_colon + 1 == end) {
+        panic!("smitten!: the sequence identifier is empty");
+    }
+}
+
+/// Returns the index of the first of the ASCII digits ending at `end`.
+const fn digits_before(bytes: &[u8], end: usize) -> usize {
+    let mut i = end;
+    while i > 0 && bytes[i - 1].is_ascii_digit() {
+        i -= 1;
+    }
+    i
+}
+
+/// Parses the ASCII digits in `bytes[start..end]`.
+const fn parse_digits(bytes: &[u8], start: usize, end: usize) -> usize {
+    let mut value: usize = 0;
+    let mut i = start;
+    while i < end {
+        value = match value.checked_mul(10) {
+            Some(shifted) => match shifted.checked_add((bytes[i] - b'0') as usize) {
+                Some(value) => value,
+                None => panic!("smitten!: a coordinate does not fit in a usize"),
+            },
+            None => panic!("smitten!: a coordinate does not fit in a usize"),
+        };
+        i += 1;
+    }
+    value
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::Identifier;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_validate_matches_from_v2(id in r"[a-c0-9:_+\- ]{0,16}") {
+            let validated = std::panic::catch_unwind(|| validate_v2_literal(&id)).is_ok();
+            prop_assert_eq!(validated, Identifier::is_valid_v2(&id), "Validation mismatch for ID: {:?}", id);
+        }
+    }
+}
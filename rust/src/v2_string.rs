@@ -0,0 +1,95 @@
+//! Serializes an `Identifier` as its compact V2 string, enabled with the
+//! `serde` feature.
+//!
+//! Use the module on a field to opt into the string form:
+//!
+//! ```
+//! # #[cfg(feature = "serde")] {
+//! use serde::{Deserialize, Serialize};
+//! use smitten::Identifier;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Hit {
+//!     #[serde(with = "smitten::v2_string")]
+//!     id: Identifier,
+//!     score: u32,
+//! }
+//! # }
+//! ```
+//!
+//! or wrap the identifier in a `V2String`, which does the same wherever it
+//! appears.
+
+use crate::Identifier;
+use alloc::string::String;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes `id` with its `Display` form, e.g. `hg38:chr1:100-200_+`.
+pub fn serialize<S: Serializer>(id: &Identifier, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(id)
+}
+
+/// Deserializes a string with `Identifier::from_v2`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Identifier, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    Identifier::from_v2(&id).map_err(de::Error::custom)
+}
+
+/// An `Identifier` that serializes as its V2 string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct V2String(pub Identifier);
+
+impl Serialize for V2String {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for V2String {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(V2String)
+    }
+}
+
+impl From<Identifier> for V2String {
+    fn from(id: Identifier) -> Self {
+        V2String(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Hit {
+        #[serde(with = "crate::v2_string")]
+        id: Identifier,
+        score: u32,
+    }
+
+    #[test]
+    fn test_v2_string_roundtrip() {
+        let test_cases = vec![
+            "hg38:chr1:100-200_+",
+            "chr1:100-200_+:10-50_-",
+            "chr1",
+        ];
+
+        for id in test_cases {
+            let hit = Hit { id: Identifier::from_v2(id).unwrap(), score: 7 };
+            let json = serde_json::to_string(&hit).unwrap();
+            assert_eq!(json, format!("{{\"id\":\"{}\",\"score\":7}}", id), "JSON mismatch for ID: {}", id);
+            assert_eq!(serde_json::from_str::<Hit>(&json).unwrap(), hit, "Round trip mismatch for ID: {}", id);
+
+            let wrapped = V2String(Identifier::from_v2(id).unwrap());
+            let json = serde_json::to_value(&wrapped).unwrap();
+            assert_eq!(json, serde_json::Value::String(id.into()), "Newtype mismatch for ID: {}", id);
+            assert_eq!(serde_json::from_value::<V2String>(json).unwrap(), wrapped);
+        }
+
+        assert!(serde_json::from_str::<V2String>("\"chr1:200-100_+\"").is_err());
+        assert!(serde_json::from_str::<V2String>("42").is_err());
+    }
+}
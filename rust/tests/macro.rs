@@ -0,0 +1,5 @@
+#[test]
+fn test_smitten_macro_rejects_invalid_literals() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
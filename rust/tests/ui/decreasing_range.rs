@@ -0,0 +1,5 @@
+use smitten::smitten;
+
+fn main() {
+    let _id = smitten!("hg38:chr1:200-100_+");
+}
@@ -0,0 +1,5 @@
+use smitten::smitten;
+
+fn main() {
+    let _id = smitten!("AMM::1002:Seq1:100-200_+");
+}